@@ -0,0 +1,55 @@
+use crate::analysis::{AnalysisReport, MarketPattern};
+use crate::config::AlertsConfig;
+use dashmap::DashMap;
+
+/// The last alert sent for a token.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SentAlert {
+    pub ts: i64,
+    pub score: f64,
+    pub pattern: MarketPattern,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AlertDecision {
+    /// First alert for the token, its cooldown has passed, or its pattern changed.
+    Send,
+    /// Still cooling down, but the score beat the last alert by `alerts.realert_score_delta`.
+    Improved { previous: f64 },
+    Suppress { remaining_secs: i64 },
+}
+
+/// Deduplicates alerts per base token mint with a cooldown, letting a token through early
+/// only when its score improved enough or it turned into a different pattern.
+#[derive(Default)]
+pub struct AlertGate {
+    sent: DashMap<String, SentAlert>,
+}
+
+impl AlertGate {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decides whether to alert on `mint` now, remembering the alert unless it is suppressed.
+    pub fn admit(&self, mint: &str, report: &AnalysisReport, now: i64, config: &AlertsConfig) -> AlertDecision {
+        let cooldown = config.cooldown_secs as i64;
+        self.sent.retain(|_, s| now - s.ts < cooldown);
+
+        let decision = match self.sent.get(mint) {
+            None => AlertDecision::Send,
+            Some(last) if last.pattern != report.pattern => AlertDecision::Send,
+            Some(last) if report.score >= last.score + config.realert_score_delta => AlertDecision::Improved { previous: last.score },
+            Some(last) => AlertDecision::Suppress { remaining_secs: cooldown - (now - last.ts) },
+        };
+        if !matches!(decision, AlertDecision::Suppress { .. }) {
+            let alert = SentAlert { ts: now, score: report.score, pattern: report.pattern.clone() };
+            self.sent.insert(mint.to_string(), alert);
+        }
+        decision
+    }
+
+    pub fn last(&self, mint: &str) -> Option<SentAlert> {
+        self.sent.get(mint).map(|s| s.clone())
+    }
+}
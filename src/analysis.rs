@@ -1,9 +1,15 @@
 use crate::models::Pair;
 use crate::config::Config;
+use crate::history::PairHistory;
 use crate::rugcheck::RugCheckResponse;
+use crate::rules::{RuleContext, RuleRegistry};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, OnceLock};
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub enum MarketPattern {
+    GoodCandidate,
     RugCandidate,
     PumpCandidate,
     StableTier1,
@@ -11,93 +17,194 @@ pub enum MarketPattern {
     Blacklisted,
     RugcheckRisk,
     BundledSupply,
+    /// Volume speeding up with rising buy pressure before the price has run.
+    EarlyAccumulation,
+    /// Sellers taking over after a run-up: a likely exit.
+    Distribution,
+    /// USD liquidity fell sharply since the previous scan.
+    LiquidityPull,
+    /// h1 volume dried up since the previous scan.
+    VolumeCollapse,
+    /// Passed the stricter new-launch checks while younger than `new_launch.max_age_minutes`.
+    NewLaunch,
+    /// Matched the `[[signals]]` entry of this name.
+    Custom(String),
     Unknown,
 }
 
-pub struct AnalysisEngine;
+/// Score every report starts from before findings are applied; final scores are clamped to 0..=100.
+pub const BASE_SCORE: f64 = 50.0;
 
-impl AnalysisEngine {
-    pub fn analyze_pair(pair: &Pair, config: &Config, rug_report: Option<&RugCheckResponse>) -> MarketPattern {
-        // 1. Check Blacklist
-        if config.blacklist.tokens.contains(&pair.pair_address) {
-            return MarketPattern::Blacklisted;
-        }
+/// How a finding affects classification.
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub enum FindingKind {
+    /// Disqualifies the pair outright and names its pattern (blacklist, rugcheck, bundles, fake volume).
+    Reject,
+    /// A minimum-threshold filter failed: the pair stays `Unknown` unless rejected.
+    Filter,
+    /// A pattern heuristic matched.
+    Signal,
+    /// Context for the reader, e.g. normalized metrics; does not affect classification.
+    Note,
+}
 
-        // 2. Rugcheck Security Check
-        if let Some(report) = rug_report {
-            if config.rugcheck.only_good && report.status != "good" {
-                return MarketPattern::RugcheckRisk;
-            }
-
-            // 3. Bundled Supply Detection
-            // Using rugcheck's bundle ratio if available, or analyzing top holder concentrations
-            if let Some(meta) = &report.file_meta {
-                if let Some(ratio) = meta.bundle_ratio {
-                    if ratio * 100.0 > config.filters.max_bundled_supply_percent {
-                        return MarketPattern::BundledSupply;
-                    }
-                }
-            }
-        }
+/// One rule that fired, with what was measured and the limit it was compared against.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Finding {
+    pub rule: String,
+    pub kind: FindingKind,
+    pub pattern: Option<MarketPattern>,
+    pub value: Option<f64>,
+    pub threshold: Option<f64>,
+    pub impact: f64,
+    pub detail: String,
+}
 
-        // 4. Fake Volume Detection (Wash Trading)
-        if let Some(liq) = &pair.liquidity {
-            if let Some(liq_usd) = liq.usd {
-                if liq_usd > 0.0 {
-                    let vlr = pair.volume.h24 / liq_usd;
-                    if vlr > config.filters.max_vlr {
-                        return MarketPattern::FakeVolume;
-                    }
-                }
-            }
-        }
+/// The full outcome of analyzing a pair: the classification, a 0-100 score and every rule that fired.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalysisReport {
+    pub pattern: MarketPattern,
+    pub score: f64,
+    pub findings: Vec<Finding>,
+}
 
-        // 5. Apply Advanced Filters from Config
-        if let Some(liq) = &pair.liquidity {
-            if let Some(liq_usd) = liq.usd {
-                if liq_usd < config.filters.min_liquidity_usd {
-                    return MarketPattern::Unknown;
+impl AnalysisReport {
+    /// Classifies from the findings: the first rejection wins, then any failed filter
+    /// leaves the pair `Unknown`, otherwise the first signal decides.
+    fn from_findings(findings: Vec<Finding>) -> Self {
+        let first = |kind: FindingKind| findings.iter().find(|f| f.kind == kind).and_then(|f| f.pattern.clone());
+        let pattern = first(FindingKind::Reject)
+            .or_else(|| {
+                if findings.iter().any(|f| f.kind == FindingKind::Filter) {
+                    Some(MarketPattern::Unknown)
+                } else {
+                    first(FindingKind::Signal)
                 }
-            } else {
-                return MarketPattern::Unknown;
-            }
-        }
+            })
+            .unwrap_or(MarketPattern::Unknown);
+        let score = (BASE_SCORE + findings.iter().map(|f| f.impact).sum::<f64>()).clamp(0.0, 100.0);
+        Self { pattern, score, findings }
+    }
 
-        if pair.volume.h24 < config.filters.min_volume_h24_usd {
-            return MarketPattern::Unknown;
+    /// Whether the paper trader should enter: momentum candidates, plus custom signals with `trade = true`.
+    pub fn is_entry(&self, config: &Config) -> bool {
+        match &self.pattern {
+            MarketPattern::GoodCandidate => true,
+            MarketPattern::Custom(name) => config.signal(name).is_some_and(|s| s.trade),
+            MarketPattern::NewLaunch => config.new_launch.trade,
+            _ => false,
         }
+    }
 
-        if let Some(mcap) = pair.market_cap {
-            if mcap < config.filters.min_mcap_usd {
-                return MarketPattern::Unknown;
-            }
+    /// Whether the pair is worth an alert: any entry, any custom signal, and momentum
+    /// patterns unless `momentum.alert` is off.
+    pub fn is_alert(&self, config: &Config) -> bool {
+        match self.pattern {
+            MarketPattern::Custom(_) | MarketPattern::NewLaunch => true,
+            MarketPattern::EarlyAccumulation | MarketPattern::Distribution => config.momentum.alert,
+            _ => self.is_entry(config),
         }
+    }
 
-        // 6. Pattern Heuristics
-        if let Some(m5_change) = pair.price_change.m5 {
-            if m5_change < -50.0 {
-                return MarketPattern::RugCandidate;
-            }
-        }
+    /// One line per finding, e.g. `fake_volume: VLR 120.00 > max 50.00 (-40)`.
+    pub fn reasons(&self) -> Vec<String> {
+        self.findings
+            .iter()
+            .map(|f| format!("{}: {} ({:+})", f.rule, f.detail, f.impact))
+            .collect()
+    }
+}
 
-        if let Some(m5_change) = pair.price_change.m5 {
-            if m5_change > 20.0 {
-                return MarketPattern::PumpCandidate;
-            }
-        }
+impl Finding {
+    /// A finding that disqualifies the pair as `pattern`.
+    pub fn reject(rule: &str, pattern: MarketPattern, impact: f64, detail: String) -> Self {
+        Self::new(rule, FindingKind::Reject, Some(pattern), impact, detail)
+    }
 
-        if let Some(mcap) = pair.market_cap {
-            if mcap > 10_000_000.0 {
-                if let Some(liq) = &pair.liquidity {
-                    if let Some(liq_usd) = liq.usd {
-                        if liq_usd > 500_000.0 && pair.volume.h24 > 1_000_000.0 {
-                            return MarketPattern::StableTier1;
-                        }
-                    }
-                }
-            }
+    /// A failed minimum-threshold filter.
+    pub fn filter(rule: &str, impact: f64, detail: String) -> Self {
+        Self::new(rule, FindingKind::Filter, None, impact, detail)
+    }
+
+    /// A matched pattern heuristic.
+    pub fn signal(rule: &str, pattern: MarketPattern, impact: f64, detail: String) -> Self {
+        Self::new(rule, FindingKind::Signal, Some(pattern), impact, detail)
+    }
+
+    /// Information that does not affect classification; `impact` is zero.
+    pub fn note(rule: &str, detail: String) -> Self {
+        Self::new(rule, FindingKind::Note, None, 0.0, detail)
+    }
+
+    /// Attaches the measured value and the threshold it was compared against.
+    pub fn measured(mut self, value: f64, threshold: Option<f64>) -> Self {
+        self.value = Some(value);
+        self.threshold = threshold;
+        self
+    }
+
+    fn new(rule: &str, kind: FindingKind, pattern: Option<MarketPattern>, impact: f64, detail: String) -> Self {
+        Self {
+            rule: rule.to_string(),
+            kind,
+            pattern,
+            value: None,
+            threshold: None,
+            impact,
+            detail,
         }
+    }
+}
+
+/// Runs a `RuleRegistry` over pairs. The built-in rules are used unless you supply your own.
+#[derive(Default)]
+pub struct AnalysisEngine {
+    rules: RuleRegistry,
+    history: Option<Arc<PairHistory>>,
+}
 
-        MarketPattern::Unknown
+impl AnalysisEngine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_rules(rules: RuleRegistry) -> Self {
+        Self { rules, history: None }
+    }
+
+    /// Lets rules compare each pair against its earlier samples. Recording new samples is up to the caller.
+    pub fn with_history(mut self, history: Arc<PairHistory>) -> Self {
+        self.history = Some(history);
+        self
+    }
+
+    pub fn history(&self) -> Option<&Arc<PairHistory>> {
+        self.history.as_ref()
+    }
+
+    pub fn rules_mut(&mut self) -> &mut RuleRegistry {
+        &mut self.rules
+    }
+
+    /// Evaluates every enabled rule (rather than stopping at the first match) and explains the result.
+    pub fn evaluate(&self, pair: &Pair, config: &Config, rug_report: Option<&RugCheckResponse>) -> AnalysisReport {
+        self.evaluate_at(pair, config, rug_report, Utc::now().timestamp())
+    }
+
+    /// `evaluate` with an explicit clock (unix seconds), used for pair ages when replaying history.
+    pub fn evaluate_at(&self, pair: &Pair, config: &Config, rug_report: Option<&RugCheckResponse>, now: i64) -> AnalysisReport {
+        let deltas = self.history.as_ref().and_then(|h| h.deltas(pair, now));
+        let ctx = RuleContext { pair, config, rug_report, now, deltas: deltas.as_ref() };
+        AnalysisReport::from_findings(self.rules.evaluate(&ctx))
+    }
+
+    /// `evaluate` with the built-in rules.
+    pub fn analyze(pair: &Pair, config: &Config, rug_report: Option<&RugCheckResponse>) -> AnalysisReport {
+        static DEFAULT: OnceLock<AnalysisEngine> = OnceLock::new();
+        DEFAULT.get_or_init(AnalysisEngine::new).evaluate(pair, config, rug_report)
+    }
+
+    pub fn analyze_pair(pair: &Pair, config: &Config, rug_report: Option<&RugCheckResponse>) -> MarketPattern {
+        Self::analyze(pair, config, rug_report).pattern
     }
 }
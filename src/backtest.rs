@@ -0,0 +1,174 @@
+use crate::analysis::AnalysisEngine;
+use crate::blacklist::BlacklistStore;
+use crate::config::Config;
+use crate::execution::{self, Fill};
+use crate::history::PairHistory;
+use crate::models::Pair;
+use crate::rugcheck::RugCheckResponse;
+use crate::rules;
+use crate::storage::Database;
+use crate::trading::{ActiveTrade, ClosedTrade, ExitReason, PaperTradingEngine};
+use anyhow::{bail, Result};
+use chrono::{TimeZone, Utc};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Replays recorded snapshots through the analysis and paper-trading engines,
+/// using each snapshot's timestamp as the clock.
+pub struct Backtester {
+    analysis: AnalysisEngine,
+    engine: PaperTradingEngine,
+    closed: Vec<ClosedTrade>,
+    /// The last snapshot of each token's pair, for closing what is still open at the end.
+    last_seen: HashMap<String, (Pair, i64)>,
+}
+
+impl Default for Backtester {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Backtester {
+    pub fn new() -> Self {
+        Self::with_engine(AnalysisEngine::new().with_history(Arc::new(PairHistory::default())))
+    }
+
+    /// Replays through a custom analysis engine (e.g. one with extra rules registered).
+    /// Snapshots are recorded into the engine's `PairHistory`, if it has one.
+    pub fn with_engine(analysis: AnalysisEngine) -> Self {
+        Self {
+            analysis,
+            engine: PaperTradingEngine::new(),
+            closed: Vec::new(),
+            last_seen: HashMap::new(),
+        }
+    }
+
+    /// Replays one snapshot: first checks exits on an open position, then looks for a new entry.
+    pub async fn step(&mut self, pair: &Pair, rug_report: Option<&RugCheckResponse>, ts: i64, config: &Config) {
+        let token = &pair.base_token.address;
+
+        if pair.price().is_some() {
+            self.last_seen.insert(token.clone(), (pair.clone(), ts));
+            if let Some(closed) = self.engine.manage_position(token, pair, ts, config).await {
+                self.closed.push(closed);
+            }
+        }
+
+        let report = self.analysis.evaluate_at(pair, config, rug_report, ts);
+        if let Some(history) = self.analysis.history() {
+            history.record(pair, ts);
+        }
+        if report.is_entry(config) && config.paper_trading.enabled {
+            self.engine.process_signal_at(pair, config, ts).await;
+        }
+    }
+
+    /// Force-closes anything still open against the last snapshot seen for that token, ignoring the slippage tolerance.
+    pub async fn finish(mut self, config: &Config) -> Vec<ClosedTrade> {
+        let open: Vec<ActiveTrade> = self.engine.active_trades.lock().await.clone();
+        for trade in open {
+            let last = self.last_seen.get(&trade.address);
+            let fill = last.and_then(|(pair, _)| execution::sell(pair, trade.remaining_tokens(), &config.execution));
+            let fill = fill.unwrap_or(Fill { price: trade.entry_price, tokens: trade.remaining_tokens(), impact_percent: None, fees_sol: 0.0 });
+            let ts = last.map_or(trade.entry_time, |(_, ts)| *ts);
+            let sol_usd = last.map_or(self.engine.sol_usd(config), |(pair, _)| self.engine.sol_usd_for(pair, config));
+            if let Some(closed) = self.engine.close_trade_at(&trade.address, &fill, sol_usd, ts, ExitReason::EndOfData).await {
+                self.closed.push(closed);
+            }
+        }
+        self.closed
+    }
+}
+
+fn format_ts(ts: i64) -> String {
+    Utc.timestamp_opt(ts, 0)
+        .single()
+        .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_else(|| ts.to_string())
+}
+
+pub fn print_report(trades: &[ClosedTrade]) {
+    println!("\n{:<10} {:<20} {:<20} {:>8} {:>14} {:>14} {:>9} {:>12}  REASON", "SYMBOL", "ENTRY", "EXIT", "HOLD", "ENTRY $", "EXIT $", "PNL %", "PNL SOL");
+    for t in trades {
+        println!(
+            "{:<10} {:<20} {:<20} {:>7}m {:>14.8} {:>14.8} {:>8.2}% {:>12.6}  {:?}",
+            t.symbol,
+            format_ts(t.entry_time),
+            format_ts(t.exit_time),
+            t.hold_secs / 60,
+            t.entry_price,
+            t.exit_price,
+            t.pnl_percent,
+            t.pnl_sol(),
+            t.reason
+        );
+    }
+
+    if trades.is_empty() {
+        println!("\nNo trades were taken.");
+        return;
+    }
+
+    let wins = trades.iter().filter(|t| t.pnl_percent > 0.0).count();
+    let total_pnl: f64 = trades.iter().map(|t| t.pnl_percent).sum();
+    let total_sol: f64 = trades.iter().map(|t| t.pnl_sol()).sum();
+    let fees_sol: f64 = trades.iter().map(|t| t.fees_sol).sum();
+    let best = trades.iter().map(|t| t.pnl_percent).fold(f64::MIN, f64::max);
+    let worst = trades.iter().map(|t| t.pnl_percent).fold(f64::MAX, f64::min);
+
+    println!("\n📊 BACKTEST SUMMARY");
+    println!("Trades:    {}", trades.len());
+    println!("Win rate:  {:.1}% ({} W / {} L)", wins as f64 / trades.len() as f64 * 100.0, wins, trades.len() - wins);
+    println!("Avg PnL:   {:.2}%", total_pnl / trades.len() as f64);
+    println!("Best:      {:.2}%", best);
+    println!("Worst:     {:.2}%", worst);
+    println!("Total PnL: {:.6} SOL", total_sol);
+    println!("Fees:      {:.6} SOL (priority; swap fees and price impact are in the prices)", fees_sol);
+}
+
+/// What `run` replayed and the trades it closed.
+#[derive(Debug)]
+pub struct BacktestReport {
+    pub replayed: usize,
+    /// Baseline lines without a pair, which cannot be replayed.
+    pub baseline: usize,
+    /// Unreadable or newer-format lines.
+    pub skipped: usize,
+    pub trades: Vec<ClosedTrade>,
+}
+
+/// Replays a snapshot file, prints the per-trade and aggregate report and returns it.
+pub async fn run(path: &str, config: &Config) -> Result<BacktestReport> {
+    if !std::path::Path::new(path).exists() {
+        bail!("No snapshot file at {}", path);
+    }
+    println!("⏪ Backtesting against {}...", path);
+    let loaded = Database::new(path).await?.load_snapshots().await?;
+    if loaded.baseline > 0 {
+        println!("⚠️ Skipped {} baseline lines, which carry no pair snapshot.", loaded.baseline);
+    }
+    if loaded.skipped > 0 {
+        println!("⚠️ Skipped {} unreadable or newer-format lines.", loaded.skipped);
+    }
+    let mut records = loaded.records;
+    // The simulated clock must only move forward.
+    records.sort_by_key(|r| r.ts);
+    println!("Replaying {} snapshots.", records.len());
+
+    let mut analysis = AnalysisEngine::new().with_history(Arc::new(PairHistory::default()));
+    analysis.rules_mut().register(rules::Blacklist::new(Arc::new(BlacklistStore::from_config(config)?)));
+    let mut backtester = Backtester::with_engine(analysis);
+    for record in &records {
+        backtester.step(&record.pair, record.rugcheck.as_ref(), record.ts, config).await;
+    }
+
+    let trades = backtester.finish(config).await;
+    print_report(&trades);
+    if !trades.is_empty() {
+        let start = config.portfolio.starting_balance_sol;
+        println!("Balance:   {:.4} → {:.4} SOL", start, start + trades.iter().map(|t| t.pnl_sol()).sum::<f64>());
+    }
+    Ok(BacktestReport { replayed: records.len(), baseline: loaded.baseline, skipped: loaded.skipped, trades })
+}
@@ -0,0 +1,23 @@
+use anyhow::Result;
+use dex_bot::mock::MockServer;
+
+/// Serves the bundled fixtures so the bot can run offline:
+///
+/// ```text
+/// cargo run --features mock --bin mock_server -- [fixtures_dir] [addr]
+/// DEXBOT_API__DEXSCREENER_URL=http://127.0.0.1:8787 \
+/// DEXBOT_API__RUGCHECK_URL=http://127.0.0.1:8787/v1 \
+/// DEXBOT_API__TELEGRAM_URL=http://127.0.0.1:8787 cargo run
+/// ```
+#[tokio::main]
+async fn main() -> Result<()> {
+    env_logger::init();
+    let mut args = std::env::args().skip(1);
+    let root = args.next().unwrap_or_else(|| "fixtures".to_string());
+    let addr = args.next().unwrap_or_else(|| "127.0.0.1:8787".to_string());
+
+    let server = MockServer::start(&root, &addr).await?;
+    println!("🧪 Mock API serving {} on {}", root, server.url());
+    tokio::signal::ctrl_c().await?;
+    Ok(())
+}
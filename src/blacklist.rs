@@ -0,0 +1,233 @@
+use crate::config::Config;
+use crate::models::Pair;
+use crate::rugcheck::RugCheckResponse;
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Mutex, PoisonError, RwLock};
+use std::time::SystemTime;
+
+/// Which blacklist an address is on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BlacklistKind {
+    /// Base token mint.
+    Token,
+    /// DEX pair / pool address.
+    Pair,
+    /// Creator / deployer wallet, as reported by rugcheck.
+    Dev,
+}
+
+impl BlacklistKind {
+    pub const ALL: [BlacklistKind; 3] = [BlacklistKind::Token, BlacklistKind::Pair, BlacklistKind::Dev];
+
+    /// What the listed address identifies, for messages.
+    pub fn subject(&self) -> &'static str {
+        match self {
+            BlacklistKind::Token => "base mint",
+            BlacklistKind::Pair => "pair",
+            BlacklistKind::Dev => "creator",
+        }
+    }
+}
+
+impl fmt::Display for BlacklistKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            BlacklistKind::Token => "token",
+            BlacklistKind::Pair => "pair",
+            BlacklistKind::Dev => "dev",
+        })
+    }
+}
+
+impl FromStr for BlacklistKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "token" | "tokens" => Ok(BlacklistKind::Token),
+            "pair" | "pairs" => Ok(BlacklistKind::Pair),
+            "dev" | "devs" => Ok(BlacklistKind::Dev),
+            other => bail!("unknown blacklist `{}` (expected token, pair or dev)", other),
+        }
+    }
+}
+
+/// One blacklisted address found on a pair.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlacklistHit {
+    pub kind: BlacklistKind,
+    pub address: String,
+}
+
+/// The on-disk blacklist file: the same three lists as `[blacklist]` in the config.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BlacklistEntries {
+    pub tokens: BTreeSet<String>,
+    pub pairs: BTreeSet<String>,
+    pub devs: BTreeSet<String>,
+}
+
+impl BlacklistEntries {
+    pub fn list(&self, kind: BlacklistKind) -> &BTreeSet<String> {
+        match kind {
+            BlacklistKind::Token => &self.tokens,
+            BlacklistKind::Pair => &self.pairs,
+            BlacklistKind::Dev => &self.devs,
+        }
+    }
+
+    fn list_mut(&mut self, kind: BlacklistKind) -> &mut BTreeSet<String> {
+        match kind {
+            BlacklistKind::Token => &mut self.tokens,
+            BlacklistKind::Pair => &mut self.pairs,
+            BlacklistKind::Dev => &mut self.devs,
+        }
+    }
+}
+
+/// EVM addresses are case-insensitive; Solana's base58 addresses are not.
+fn normalize(address: &str) -> String {
+    let address = address.trim();
+    if address.starts_with("0x") {
+        address.to_ascii_lowercase()
+    } else {
+        address.to_string()
+    }
+}
+
+/// Blacklist entries kept in a TOML file (`blacklist.file`) that can be changed while the bot runs:
+/// `add` / `remove` write the file, and `refresh` picks up edits made by other processes.
+/// Entries from `[blacklist]` in the config are checked as well.
+pub struct BlacklistStore {
+    path: Option<PathBuf>,
+    entries: RwLock<BlacklistEntries>,
+    loaded_mtime: Mutex<Option<SystemTime>>,
+}
+
+impl Default for BlacklistStore {
+    fn default() -> Self {
+        Self::in_memory()
+    }
+}
+
+impl BlacklistStore {
+    /// A store with no file; entries added at runtime are lost on exit.
+    pub fn in_memory() -> Self {
+        Self {
+            path: None,
+            entries: RwLock::new(BlacklistEntries::default()),
+            loaded_mtime: Mutex::new(None),
+        }
+    }
+
+    /// Loads `path`; a missing file is an empty blacklist and is created on the first `add`.
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let store = Self {
+            path: Some(path.into()),
+            ..Self::in_memory()
+        };
+        store.reload()?;
+        Ok(store)
+    }
+
+    /// The store for `blacklist.file`, or an in-memory one when no file is configured.
+    pub fn from_config(config: &Config) -> Result<Self> {
+        match &config.blacklist.file {
+            Some(path) => Self::open(path),
+            None => Ok(Self::in_memory()),
+        }
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    fn reload(&self) -> Result<()> {
+        let Some(path) = &self.path else { return Ok(()) };
+        let (entries, mtime) = match std::fs::read_to_string(path) {
+            Ok(contents) => {
+                let entries: BlacklistEntries = toml::from_str(&contents).with_context(|| format!("invalid blacklist file {}", path.display()))?;
+                (entries, std::fs::metadata(path).and_then(|m| m.modified()).ok())
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => (BlacklistEntries::default(), None),
+            Err(e) => return Err(e).with_context(|| format!("failed to read blacklist file {}", path.display())),
+        };
+        let mut normalized = BlacklistEntries::default();
+        for kind in BlacklistKind::ALL {
+            *normalized.list_mut(kind) = entries.list(kind).iter().map(|a| normalize(a)).collect();
+        }
+        *self.entries.write().unwrap_or_else(PoisonError::into_inner) = normalized;
+        *self.loaded_mtime.lock().unwrap_or_else(PoisonError::into_inner) = mtime;
+        Ok(())
+    }
+
+    /// Re-reads the file if it changed since it was last loaded. Returns whether it was reloaded.
+    pub fn refresh(&self) -> Result<bool> {
+        let Some(path) = &self.path else { return Ok(false) };
+        let mtime = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+        if mtime == *self.loaded_mtime.lock().unwrap_or_else(PoisonError::into_inner) {
+            return Ok(false);
+        }
+        self.reload()?;
+        Ok(true)
+    }
+
+    /// Blacklists `address` and persists the file. Returns false if it was already listed.
+    pub fn add(&self, kind: BlacklistKind, address: &str) -> Result<bool> {
+        self.update(|entries| entries.list_mut(kind).insert(normalize(address)))
+    }
+
+    /// Removes `address` and persists the file. Returns false if it was not listed.
+    pub fn remove(&self, kind: BlacklistKind, address: &str) -> Result<bool> {
+        self.update(|entries| entries.list_mut(kind).remove(&normalize(address)))
+    }
+
+    fn update(&self, change: impl FnOnce(&mut BlacklistEntries) -> bool) -> Result<bool> {
+        self.refresh()?;
+        let mut entries = self.entries.write().unwrap_or_else(PoisonError::into_inner);
+        if !change(&mut entries) {
+            return Ok(false);
+        }
+        if let Some(path) = &self.path {
+            let tmp = path.with_extension("tmp");
+            std::fs::write(&tmp, toml::to_string(&*entries)?).with_context(|| format!("failed to write {}", tmp.display()))?;
+            std::fs::rename(&tmp, path)?;
+            *self.loaded_mtime.lock().unwrap_or_else(PoisonError::into_inner) = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+        }
+        Ok(true)
+    }
+
+    pub fn entries(&self) -> BlacklistEntries {
+        self.entries.read().unwrap_or_else(PoisonError::into_inner).clone()
+    }
+
+    pub fn contains(&self, kind: BlacklistKind, address: &str) -> bool {
+        self.entries.read().unwrap_or_else(PoisonError::into_inner).list(kind).contains(&normalize(address))
+    }
+
+    /// Every list the pair matches, checking the base mint, the pair address and the
+    /// rugcheck-reported creator against both this store and `config.blacklist`.
+    pub fn check(&self, pair: &Pair, rug_report: Option<&RugCheckResponse>, config: &Config) -> Vec<BlacklistHit> {
+        let candidates = [
+            (BlacklistKind::Token, Some(pair.base_token.address.as_str())),
+            (BlacklistKind::Pair, Some(pair.pair_address.as_str())),
+            (BlacklistKind::Dev, rug_report.and_then(|r| r.creator.as_deref())),
+        ];
+        candidates
+            .into_iter()
+            .filter_map(|(kind, address)| {
+                let address = address.filter(|a| !a.is_empty())?;
+                let normalized = normalize(address);
+                let in_config = config.blacklist.list(kind).iter().any(|a| normalize(a) == normalized);
+                (in_config || self.contains(kind, address)).then(|| BlacklistHit { kind, address: address.to_string() })
+            })
+            .collect()
+    }
+}
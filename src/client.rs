@@ -1,42 +1,41 @@
+use crate::config::HttpConfig;
+use crate::http::HttpClient;
 use crate::models::DexScreenerResponse;
-use anyhow::{Result, anyhow};
-use reqwest::Client;
-use std::time::Duration;
+use anyhow::{Context, Result};
+use std::sync::Arc;
+
+pub const DEXSCREENER_API_URL: &str = "https://api.dexscreener.com";
 
 pub struct DexScreenerClient {
-    client: Client,
+    http: Arc<HttpClient>,
+    base_url: String,
 }
 
 impl DexScreenerClient {
-    pub fn new() -> Self {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(10))
-            .build()
-            .unwrap();
-        Self { client }
+    pub fn new() -> Result<Self> {
+        Self::with_base_url(DEXSCREENER_API_URL)
     }
 
-    pub async fn search_pairs(&self, query: &str) -> Result<DexScreenerResponse> {
-        let url = format!("https://api.dexscreener.com/latest/dex/search?q={}", query);
-        let resp = self.client.get(&url).send().await?;
-        
-        if resp.status().is_success() {
-            let data = resp.json::<DexScreenerResponse>().await?;
-            Ok(data)
-        } else {
-            Err(anyhow!("Failed to fetch search results: {}", resp.status()))
+    /// A client with its own `HttpClient` using the default `[http]` settings.
+    pub fn with_base_url(base_url: &str) -> Result<Self> {
+        Ok(Self::with_http(base_url, Arc::new(HttpClient::new(&HttpConfig::default())?)))
+    }
+
+    pub fn with_http(base_url: &str, http: Arc<HttpClient>) -> Self {
+        Self {
+            http,
+            base_url: base_url.trim_end_matches('/').to_string(),
         }
     }
 
+    pub async fn search_pairs(&self, query: &str) -> Result<DexScreenerResponse> {
+        let url = reqwest::Url::parse_with_params(&format!("{}/latest/dex/search", self.base_url), &[("q", query)])
+            .context("Invalid DexScreener base URL")?;
+        self.http.get_json(url.as_str()).await.context("Failed to fetch search results")
+    }
+
     pub async fn get_token_pairs(&self, token_address: &str) -> Result<DexScreenerResponse> {
-        let url = format!("https://api.dexscreener.com/latest/dex/tokens/{}", token_address);
-        let resp = self.client.get(&url).send().await?;
-        
-        if resp.status().is_success() {
-            let data = resp.json::<DexScreenerResponse>().await?;
-            Ok(data)
-        } else {
-            Err(anyhow!("Failed to fetch token pairs: {}", resp.status()))
-        }
+        let url = format!("{}/latest/dex/tokens/{}", self.base_url, token_address);
+        self.http.get_json(&url).await.context("Failed to fetch token pairs")
     }
 }
@@ -1,58 +1,1158 @@
+use crate::blacklist::BlacklistKind;
+use crate::expr::Expr;
+use crate::models::Token;
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+
+/// Prefix for environment variables that override values from the config file.
+/// `DEXBOT_FILTERS__MIN_LIQUIDITY_USD=2000` sets `filters.min_liquidity_usd`.
+pub const ENV_PREFIX: &str = "DEXBOT_";
+
+/// Keys whose overrides are always taken verbatim, since ids and tokens often look like numbers.
+const STRING_KEYS: &[&str] = &["telegram.bot_token", "telegram.chat_id", "telegram.bonkbot_ref", "rugcheck.api_key"];
+
+/// Keys never printed in reload diffs.
+const SECRET_KEYS: &[&str] = &["telegram.bot_token", "rugcheck.api_key"];
+
+/// Default `markets.native_quotes`: wrapped SOL, WETH on Ethereum and Base, and WBNB.
+pub const NATIVE_QUOTES: &[&str] = &[
+    "So11111111111111111111111111111111111111112",
+    "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2",
+    "0x4200000000000000000000000000000000000006",
+    "0xbb4CdB9CBd36B01bD1cBaEBF2De08d9173bc095c",
+];
+
+/// Default `markets.stable_quotes`: USDC, USDT and PYUSD on Solana; USDC, USDT, DAI, PYUSD and USDe
+/// on Ethereum; USDC on Base; USDT and USDC on BSC.
+pub const STABLE_QUOTES: &[&str] = &[
+    "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v",
+    "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB",
+    "2b1kV6DkPAnxd5ixfnxCpjxmKwqjjaYmCZfHsFu24GXo",
+    "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48",
+    "0xdAC17F958D2ee523a2206206994597C13D831ec7",
+    "0x6B175474E89094C44Da98b954EedeAC495271d0F",
+    "0x6c3ea9036406852006290770BEdFcAbA0e23A0e8",
+    "0x4c9EDD5852cd905f086C759E8383e09bff1E68B3",
+    "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913",
+    "0x55d398326f99059fF775485246999027B3197955",
+    "0x8AC76a51cc950d9822D68b83fE1Ad97B32Cd580d",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct Config {
     pub filters: Filters,
+    pub chains: ChainsConfig,
+    pub markets: MarketsConfig,
     pub blacklist: Blacklist,
     pub queries: Vec<String>,
     pub rugcheck: RugcheckConfig,
+    pub telegram: TelegramConfig,
+    pub paper_trading: PaperTradingConfig,
+    pub execution: ExecutionConfig,
+    pub portfolio: PortfolioConfig,
+    pub momentum: MomentumConfig,
+    pub new_launch: NewLaunchConfig,
+    pub history: HistoryConfig,
+    pub liquidity_watch: LiquidityWatchConfig,
+    pub alerts: AlertsConfig,
+    pub api: ApiConfig,
+    pub http: HttpConfig,
+    /// Per-rule overrides keyed by `Rule::name`; rules not listed run with defaults.
+    pub rules: BTreeMap<String, RuleSettings>,
+    /// Custom signals written in the filter expression language, evaluated after the built-in rules.
+    pub signals: Vec<SignalConfig>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct Filters {
     pub min_liquidity_usd: f64,
     pub min_volume_h24_usd: f64,
     pub min_mcap_usd: f64,
     pub max_vlr: f64, // Volume to Liquidity Ratio
     pub max_bundled_supply_percent: f64,
+    /// Skip pairs younger than this many minutes.
+    pub min_pair_age_minutes: Option<f64>,
+    /// Skip pairs older than this many minutes.
+    pub max_pair_age_minutes: Option<f64>,
+}
+
+/// Which chains are scanned, and per-chain overrides of the filters and security checks.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ChainsConfig {
+    /// DexScreener chain ids to scan, e.g. `["solana", "base"]`. Empty scans every chain.
+    pub allow: Vec<String>,
+    /// Overrides keyed by chain id.
+    pub profiles: BTreeMap<String, ChainProfile>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ChainProfile {
+    /// Replaces `filters.min_liquidity_usd` on this chain.
+    pub min_liquidity_usd: Option<f64>,
+    /// Replaces `filters.min_volume_h24_usd` on this chain.
+    pub min_volume_h24_usd: Option<f64>,
+    /// Replaces `filters.min_mcap_usd` on this chain.
+    pub min_mcap_usd: Option<f64>,
+    /// Where token security reports come from; defaults to the first provider supporting the chain.
+    pub security: Option<SecurityProvider>,
+    /// Only pairs quoted in these tokens (mint addresses) pass. Empty allows any quote token.
+    pub quote_tokens: Vec<String>,
+}
+
+/// DEX and quote-token allow/deny lists, and how quote tokens weigh liquidity and volume.
+/// Quote tokens are listed by mint address; symbols are set by the token's creator and can be spoofed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MarketsConfig {
+    /// DexScreener dex ids to accept, e.g. `["raydium", "orca"]`. Empty accepts any DEX.
+    pub dex_allow: Vec<String>,
+    pub dex_deny: Vec<String>,
+    /// Quote tokens to accept. Empty accepts any quote token.
+    pub quote_allow: Vec<String>,
+    pub quote_deny: Vec<String>,
+    /// Quote tokens counted as the chain's native asset.
+    pub native_quotes: Vec<String>,
+    /// Quote tokens counted as stablecoins.
+    pub stable_quotes: Vec<String>,
+    /// Multipliers applied to liquidity and volume by quote kind. Pools quoted in a volatile token
+    /// are worth less than their USD figure suggests.
+    pub native_quote_weight: f64,
+    pub stable_quote_weight: f64,
+    pub other_quote_weight: f64,
+}
+
+/// How a pair's quote token is classified for normalization.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuoteKind {
+    Native,
+    Stable,
+    Other,
+}
+
+impl fmt::Display for QuoteKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            QuoteKind::Native => "native",
+            QuoteKind::Stable => "stable",
+            QuoteKind::Other => "other",
+        })
+    }
+}
+
+/// Whether `token`'s mint address is listed in `list`. Solana addresses are base58 and compared
+/// exactly; EVM (`0x…`) addresses are hex and compared case-insensitively.
+pub fn lists_token(list: &[String], token: &Token) -> bool {
+    list.iter().any(|t| if t.starts_with("0x") { t.eq_ignore_ascii_case(&token.address) } else { *t == token.address })
+}
+
+/// Whether a list entry looks like a mint address rather than a symbol: 32 to 44 base58
+/// characters, or `0x` and 40 hex digits.
+fn is_token_address(entry: &str) -> bool {
+    match entry.strip_prefix("0x") {
+        Some(hex) => hex.len() == 40 && hex.chars().all(|c| c.is_ascii_hexdigit()),
+        None => (32..=44).contains(&entry.len()) && entry.chars().all(|c| c.is_ascii_alphanumeric() && !"0OIl".contains(c)),
+    }
+}
+
+impl MarketsConfig {
+    pub fn quote_kind(&self, token: &Token) -> QuoteKind {
+        if lists_token(&self.native_quotes, token) {
+            QuoteKind::Native
+        } else if lists_token(&self.stable_quotes, token) {
+            QuoteKind::Stable
+        } else {
+            QuoteKind::Other
+        }
+    }
+
+    pub fn quote_weight(&self, kind: QuoteKind) -> f64 {
+        match kind {
+            QuoteKind::Native => self.native_quote_weight,
+            QuoteKind::Stable => self.stable_quote_weight,
+            QuoteKind::Other => self.other_quote_weight,
+        }
+    }
+}
+
+/// A token security report source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SecurityProvider {
+    /// rugcheck.xyz, Solana only.
+    Rugcheck,
+    /// No security checks; rules that need a report see none.
+    #[serde(rename = "none")]
+    Disabled,
+}
+
+impl SecurityProvider {
+    pub const ALL: [SecurityProvider; 1] = [SecurityProvider::Rugcheck];
+
+    pub fn supports(&self, chain_id: &str) -> bool {
+        match self {
+            SecurityProvider::Rugcheck => chain_id == "solana",
+            SecurityProvider::Disabled => true,
+        }
+    }
 }
 
+impl fmt::Display for SecurityProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            SecurityProvider::Rugcheck => "rugcheck",
+            SecurityProvider::Disabled => "none",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct Blacklist {
+    /// Base token mints.
     pub tokens: Vec<String>,
+    /// DEX pair / pool addresses.
+    pub pairs: Vec<String>,
+    /// Creator / deployer wallets, matched against the rugcheck report.
     pub devs: Vec<String>,
+    /// Additional entries managed at runtime (`dex-bot --blacklist ...`). The path is fixed at
+    /// startup; the file's contents are reloaded every scan cycle when it changes.
+    pub file: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct RugcheckConfig {
     pub api_key: Option<String>,
     pub only_good: bool,
+    /// Cache lifetime of "good" reports. Read once at startup, like the other cache settings.
+    pub cache_good_ttl_secs: u64,
+    /// Cache lifetime of every other status.
+    pub cache_risky_ttl_secs: u64,
+    /// Persist the report cache here between runs.
+    pub cache_path: Option<String>,
+    /// Reject when the report's aggregate risk score exceeds this.
+    pub max_score: Option<i32>,
+    /// Reject when any risk name contains one of these (case-insensitive), e.g. "mint authority".
+    pub deny_risks: Vec<String>,
+    /// Maximum number of risks allowed per level, e.g. `{ danger = 0, warn = 2 }`.
+    pub max_risks_per_level: BTreeMap<String, usize>,
 }
 
-impl Config {
-    /// Pure Rust configuration initialization.
-    /// Professional setups often prefer compile-time or code-defined defaults for critical bot logic.
-    pub fn new() -> Self {
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TelegramConfig {
+    pub bot_token: String,
+    pub chat_id: String,
+    pub bonkbot_ref: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PaperTradingConfig {
+    pub enabled: bool,
+    pub buy_amount_sol: f64,
+    /// Sells the whole position at this gain, unless `take_profit_ladder` is set.
+    pub take_profit_percent: f64,
+    pub stop_loss_percent: f64,
+    /// Partial take-profits, e.g. sell 50% at +50% then 25% at +100%. What the ladder leaves
+    /// unsold rides on the stops and `max_hold_secs`.
+    pub take_profit_ladder: Vec<TakeProfitStep>,
+    /// Exit when the price falls this far below its peak since entry, once in profit.
+    pub trailing_stop_percent: Option<f64>,
+    /// After the first take-profit fill, exit the rest if the price returns to the entry price.
+    pub breakeven_after_first_tp: bool,
+    /// Exit whatever is left after holding this long.
+    pub max_hold_secs: Option<u64>,
+    pub journal_path: String,
+}
+
+/// One rung of `paper_trading.take_profit_ladder`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TakeProfitStep {
+    /// Gain over the entry price that triggers the step.
+    pub at_percent: f64,
+    /// Share of the original position to sell.
+    pub sell_percent: f64,
+}
+
+/// How paper trades are filled: constant-product price impact from the pool reserves, the DEX swap
+/// fee and a priority fee, on both entry and exit. Disabled, trades fill at the quoted price.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ExecutionConfig {
+    pub enabled: bool,
+    /// DEX fee taken from every swap's input.
+    pub swap_fee_percent: f64,
+    /// Paid per swap, on top of the trade.
+    pub priority_fee_sol: f64,
+    /// Fills whose price impact exceeds this are rejected, as the swap would revert.
+    /// Only entries and take-profits honor it: stop-loss, trailing, max-hold and other protective exits fill regardless.
+    pub slippage_tolerance_percent: f64,
+    /// USD per SOL until the live price feed has a price, used to size buys in pools not quoted in SOL.
+    pub sol_price_usd: f64,
+}
+
+/// The paper portfolio's SOL balance and its equity curve.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PortfolioConfig {
+    /// Virtual SOL the paper trader starts with; entries it can't afford are skipped.
+    pub starting_balance_sol: f64,
+    /// Portfolio snapshots are appended here on every trade-monitor tick.
+    pub equity_path: String,
+    /// How long a SOL/USD price from DexScreener is reused.
+    pub sol_price_ttl_secs: u64,
+}
+
+/// Base URLs of the external services, so the bot can be pointed at a mock server.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ApiConfig {
+    pub dexscreener_url: String,
+    pub rugcheck_url: String,
+    pub telegram_url: Option<String>,
+}
+
+/// New-launch sniping: pairs younger than `max_age_minutes` are scanned first, must pass
+/// stricter checks, and otherwise classify as `NewLaunch`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NewLaunchConfig {
+    pub enabled: bool,
+    pub max_age_minutes: f64,
+    /// Replaces `filters.min_liquidity_usd` for new launches when higher.
+    pub min_liquidity_usd: f64,
+    /// New launches without a rugcheck report are rejected, on chains whose security provider is enabled.
+    pub require_rugcheck: bool,
+    /// Maximum rugcheck risk score for new launches.
+    pub max_rugcheck_score: i32,
+    /// Paper-trade pairs classified as `NewLaunch`; otherwise they only alert.
+    pub trade: bool,
+}
+
+/// Per-pair history across scan cycles and the rules built on it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HistoryConfig {
+    /// Samples kept per pair. Read once at startup, like `retention_secs` and `path`.
+    pub max_samples: usize,
+    /// Pairs not seen for this long are forgotten.
+    pub retention_secs: u64,
+    /// Persist the history here between runs.
+    pub path: Option<String>,
+    /// Deltas against a sample older than this are ignored by the rules.
+    pub max_gap_secs: u64,
+    /// Reject as `LiquidityPull` when USD liquidity fell by at least this percent since the previous sample.
+    pub liquidity_pull_percent: f64,
+    /// Reject as `VolumeCollapse` when h1 volume fell by at least this percent since the previous sample.
+    pub volume_collapse_percent: f64,
+}
+
+/// Watches the pools of held and recently alerted tokens on every trade-monitor tick.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LiquidityWatchConfig {
+    pub enabled: bool,
+    /// Alert (and force a paper exit) when liquidity falls this far below its peak within `window_secs`.
+    pub drop_percent: f64,
+    pub window_secs: u64,
+    /// How long an alerted token stays watched. Held tokens are watched while the position is open.
+    pub watch_alerted_secs: u64,
+}
+
+/// Telegram alert deduplication, keyed by base token mint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AlertsConfig {
+    /// After an alert, the same token is not alerted again for this long, whichever pair or query surfaces it.
+    pub cooldown_secs: u64,
+    /// Re-alert during the cooldown when the score beats the last alert's by at least this much.
+    pub realert_score_delta: f64,
+}
+
+/// Thresholds for the multi-timeframe `early_accumulation` and `distribution` rules.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MomentumConfig {
+    /// Send Telegram alerts for EarlyAccumulation and Distribution pairs (they are never paper-traded).
+    pub alert: bool,
+    /// Minimum m5 volume run-rate relative to h1 volume for accumulation.
+    pub min_volume_acceleration: f64,
+    /// Minimum m5 and h1 buys per sell for accumulation.
+    pub min_buy_sell_ratio: f64,
+    /// Accumulation must not have run yet: h1 price change at most this.
+    pub max_accumulation_h1_change: f64,
+    /// Distribution follows a run-up: h24 price change at least this.
+    pub min_distribution_h24_change: f64,
+    /// Distribution means sellers dominate: m5 and h1 buys per sell at most this.
+    pub max_distribution_buy_sell_ratio: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RuleSettings {
+    pub enabled: bool,
+    /// Multiplies the score impact of the rule's findings.
+    pub weight: f64,
+}
+
+impl Default for RuleSettings {
+    fn default() -> Self {
         Self {
-            filters: Filters {
-                min_liquidity_usd: 1000.0,
-                min_volume_h24_usd: 5000.0,
-                min_mcap_usd: 10000.0,
-                max_vlr: 50.0,
-                max_bundled_supply_percent: 25.0, // Flag if top holders have > 25%
-            },
-            blacklist: Blacklist {
-                tokens: vec![
-                    "0x0000000000000000000000000000000000000000".to_string(),
-                ],
-                devs: vec![
-                    "rugged_dev_id_1".to_string(),
-                ],
-            },
+            enabled: true,
+            weight: 1.0,
+        }
+    }
+}
+
+/// A named signal that fires when `when` holds; see `crate::expr` for the syntax.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SignalConfig {
+    pub name: String,
+    pub when: Expr,
+    /// Score impact when the signal fires.
+    #[serde(default = "default_signal_impact")]
+    pub impact: f64,
+    /// Paper-trade the pair when this signal classifies it; otherwise it only alerts.
+    #[serde(default)]
+    pub trade: bool,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+fn default_signal_impact() -> f64 {
+    10.0
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Outbound HTTP behaviour shared by every API client. Read once at startup.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HttpConfig {
+    pub timeout_secs: u64,
+    pub max_retries: u32,
+    pub base_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+    /// Requests per second for hosts not listed in `rate_limits`.
+    pub default_rps: f64,
+    /// Requests per second keyed by host (`host:port` when a port is given).
+    pub rate_limits: BTreeMap<String, f64>,
+}
+
+impl Default for Filters {
+    fn default() -> Self {
+        Self {
+            min_liquidity_usd: 1000.0,
+            min_volume_h24_usd: 5000.0,
+            min_mcap_usd: 10000.0,
+            max_vlr: 50.0,
+            max_bundled_supply_percent: 25.0, // Flag if top holders have > 25%
+            min_pair_age_minutes: None,
+            max_pair_age_minutes: None,
+        }
+    }
+}
+
+impl Default for Blacklist {
+    fn default() -> Self {
+        Self {
+            tokens: vec![
+                "0x0000000000000000000000000000000000000000".to_string(),
+            ],
+            pairs: Vec::new(),
+            devs: vec![
+                "rugged_dev_id_1".to_string(),
+            ],
+            file: None,
+        }
+    }
+}
+
+impl Blacklist {
+    pub fn list(&self, kind: BlacklistKind) -> &[String] {
+        match kind {
+            BlacklistKind::Token => &self.tokens,
+            BlacklistKind::Pair => &self.pairs,
+            BlacklistKind::Dev => &self.devs,
+        }
+    }
+}
+
+impl Default for MarketsConfig {
+    fn default() -> Self {
+        let list = |tokens: &[&str]| tokens.iter().map(|t| t.to_string()).collect();
+        Self {
+            dex_allow: Vec::new(),
+            dex_deny: Vec::new(),
+            quote_allow: Vec::new(),
+            quote_deny: Vec::new(),
+            native_quotes: list(NATIVE_QUOTES),
+            stable_quotes: list(STABLE_QUOTES),
+            native_quote_weight: 1.0,
+            stable_quote_weight: 1.0,
+            other_quote_weight: 0.5,
+        }
+    }
+}
+
+impl Default for RugcheckConfig {
+    fn default() -> Self {
+        Self {
+            api_key: None,
+            only_good: true,
+            cache_good_ttl_secs: 600,
+            cache_risky_ttl_secs: 3600,
+            cache_path: None,
+            max_score: None,
+            deny_risks: Vec::new(),
+            max_risks_per_level: BTreeMap::new(),
+        }
+    }
+}
+
+impl Default for TelegramConfig {
+    fn default() -> Self {
+        Self {
+            bot_token: "YOUR_BOT_TOKEN".to_string(),
+            chat_id: "YOUR_CHAT_ID".to_string(),
+            bonkbot_ref: "ref_code".to_string(),
+        }
+    }
+}
+
+impl Default for PaperTradingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            buy_amount_sol: 0.1,
+            take_profit_percent: 50.0,
+            stop_loss_percent: 25.0,
+            take_profit_ladder: Vec::new(),
+            trailing_stop_percent: None,
+            breakeven_after_first_tp: false,
+            max_hold_secs: None,
+            journal_path: "paper_trades.jsonl".to_string(),
+        }
+    }
+}
+
+impl Default for ExecutionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            swap_fee_percent: 0.25,
+            priority_fee_sol: 0.0005,
+            slippage_tolerance_percent: 10.0,
+            sol_price_usd: 150.0,
+        }
+    }
+}
+
+impl Default for PortfolioConfig {
+    fn default() -> Self {
+        Self {
+            starting_balance_sol: 10.0,
+            equity_path: "equity_curve.jsonl".to_string(),
+            sol_price_ttl_secs: 60,
+        }
+    }
+}
+
+impl Default for NewLaunchConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_age_minutes: 30.0,
+            min_liquidity_usd: 10_000.0,
+            require_rugcheck: true,
+            max_rugcheck_score: 1000,
+            trade: false,
+        }
+    }
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            max_samples: 120,
+            retention_secs: 86_400,
+            path: None,
+            max_gap_secs: 900,
+            liquidity_pull_percent: 50.0,
+            volume_collapse_percent: 80.0,
+        }
+    }
+}
+
+impl Default for LiquidityWatchConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            drop_percent: 50.0,
+            window_secs: 900,
+            watch_alerted_secs: 6 * 3600,
+        }
+    }
+}
+
+impl Default for AlertsConfig {
+    fn default() -> Self {
+        Self {
+            cooldown_secs: 3600,
+            realert_score_delta: 10.0,
+        }
+    }
+}
+
+impl Default for MomentumConfig {
+    fn default() -> Self {
+        Self {
+            alert: true,
+            min_volume_acceleration: 2.0,
+            min_buy_sell_ratio: 1.5,
+            max_accumulation_h1_change: 30.0,
+            min_distribution_h24_change: 50.0,
+            max_distribution_buy_sell_ratio: 0.7,
+        }
+    }
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self {
+            dexscreener_url: crate::client::DEXSCREENER_API_URL.to_string(),
+            rugcheck_url: crate::rugcheck::RUGCHECK_API_URL.to_string(),
+            telegram_url: None,
+        }
+    }
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            timeout_secs: 10,
+            max_retries: 3,
+            base_backoff_ms: 500,
+            max_backoff_ms: 30_000,
+            default_rps: 5.0,
+            rate_limits: BTreeMap::from([
+                ("api.dexscreener.com".to_string(), 5.0),
+                ("api.rugcheck.xyz".to_string(), 2.0),
+                ("api.telegram.org".to_string(), 1.0),
+            ]),
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            filters: Filters::default(),
+            chains: ChainsConfig::default(),
+            markets: MarketsConfig::default(),
+            blacklist: Blacklist::default(),
             queries: vec![
                 "pump".to_string(),
                 "pepe".to_string(),
                 "solana".to_string(),
                 "moon".to_string(),
             ],
-            rugcheck: RugcheckConfig {
-                api_key: None,
-                only_good: true,
+            rugcheck: RugcheckConfig::default(),
+            telegram: TelegramConfig::default(),
+            paper_trading: PaperTradingConfig::default(),
+            execution: ExecutionConfig::default(),
+            portfolio: PortfolioConfig::default(),
+            momentum: MomentumConfig::default(),
+            new_launch: NewLaunchConfig::default(),
+            history: HistoryConfig::default(),
+            liquidity_watch: LiquidityWatchConfig::default(),
+            alerts: AlertsConfig::default(),
+            api: ApiConfig::default(),
+            http: HttpConfig::default(),
+            rules: BTreeMap::new(),
+            signals: Vec::new(),
+        }
+    }
+}
+
+impl Config {
+    /// Built-in defaults plus the `TELEGRAM_*` credentials from the environment / `.env`.
+    pub fn new() -> Self {
+        dotenv::dotenv().ok();
+        let mut config = Self::default();
+        if let Ok(token) = std::env::var("TELEGRAM_BOT_TOKEN") {
+            config.telegram.bot_token = token;
+        }
+        if let Ok(chat_id) = std::env::var("TELEGRAM_CHAT_ID") {
+            config.telegram.chat_id = chat_id;
+        }
+        config
+    }
+
+    /// Reads a TOML config file, applies `DEXBOT_*` and `TELEGRAM_*` overrides, and validates the result.
+    /// Keys missing from the file keep their built-in defaults.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read config file {}", path.display()))?;
+        Self::from_toml_str(&contents).with_context(|| format!("invalid config file {}", path.display()))
+    }
+
+    /// Same as `load`, for when no config file is present: defaults plus environment overrides.
+    pub fn from_env() -> Result<Self> {
+        Self::from_toml_str("")
+    }
+
+    pub fn from_toml_str(contents: &str) -> Result<Self> {
+        dotenv::dotenv().ok();
+        Self::from_toml_str_with_env(contents, std::env::vars())
+    }
+
+    /// `from_toml_str` with overrides taken from `vars` instead of the process environment.
+    pub fn from_toml_str_with_env(contents: &str, vars: impl IntoIterator<Item = (String, String)>) -> Result<Self> {
+        let mut table: toml::Table = contents.parse()?;
+        apply_env_overrides(&mut table, vars.into_iter())?;
+        let config: Config = toml::Value::Table(table).try_into()?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// The `[[signals]]` entry called `name`.
+    pub fn signal(&self, name: &str) -> Option<&SignalConfig> {
+        self.signals.iter().find(|s| s.name == name)
+    }
+
+    pub fn chain_allowed(&self, chain_id: &str) -> bool {
+        self.chains.allow.is_empty() || self.chains.allow.iter().any(|c| c == chain_id)
+    }
+
+    /// `filters` with the chain's profile overrides applied.
+    pub fn filters_for(&self, chain_id: &str) -> Filters {
+        let mut filters = self.filters.clone();
+        if let Some(profile) = self.chains.profiles.get(chain_id) {
+            filters.min_liquidity_usd = profile.min_liquidity_usd.unwrap_or(filters.min_liquidity_usd);
+            filters.min_volume_h24_usd = profile.min_volume_h24_usd.unwrap_or(filters.min_volume_h24_usd);
+            filters.min_mcap_usd = profile.min_mcap_usd.unwrap_or(filters.min_mcap_usd);
+        }
+        filters
+    }
+
+    /// The security provider to ask about tokens on `chain_id`: the profile's choice, otherwise the
+    /// first provider supporting the chain, otherwise `Disabled`.
+    pub fn security_provider(&self, chain_id: &str) -> SecurityProvider {
+        match self.chains.profiles.get(chain_id).and_then(|p| p.security) {
+            Some(provider) => provider,
+            None => SecurityProvider::ALL.into_iter().find(|p| p.supports(chain_id)).unwrap_or(SecurityProvider::Disabled),
+        }
+    }
+
+    /// Checks value ranges and cross-field constraints, reporting every problem at once.
+    pub fn validate(&self) -> Result<()> {
+        let mut errors = Vec::new();
+        let mut check = |ok: bool, msg: String| {
+            if !ok {
+                errors.push(msg);
+            }
+        };
+
+        let f = &self.filters;
+        check(f.min_liquidity_usd >= 0.0, format!("filters.min_liquidity_usd must be >= 0 (got {})", f.min_liquidity_usd));
+        check(f.min_volume_h24_usd >= 0.0, format!("filters.min_volume_h24_usd must be >= 0 (got {})", f.min_volume_h24_usd));
+        check(f.min_mcap_usd >= 0.0, format!("filters.min_mcap_usd must be >= 0 (got {})", f.min_mcap_usd));
+        check(f.max_vlr > 0.0, format!("filters.max_vlr must be > 0 (got {})", f.max_vlr));
+        check(
+            f.max_bundled_supply_percent > 0.0 && f.max_bundled_supply_percent <= 100.0,
+            format!("filters.max_bundled_supply_percent must be in (0, 100] (got {})", f.max_bundled_supply_percent),
+        );
+
+        for (key, age) in [("min_pair_age_minutes", f.min_pair_age_minutes), ("max_pair_age_minutes", f.max_pair_age_minutes)] {
+            if let Some(age) = age {
+                check(age >= 0.0, format!("filters.{} must be >= 0 (got {})", key, age));
+            }
+        }
+        if let (Some(min), Some(max)) = (f.min_pair_age_minutes, f.max_pair_age_minutes) {
+            check(min <= max, format!("filters.min_pair_age_minutes ({}) must be <= filters.max_pair_age_minutes ({})", min, max));
+        }
+
+        check(
+            self.chains.allow.iter().all(|c| !c.trim().is_empty()),
+            "chains.allow must not contain empty chain ids".to_string(),
+        );
+        for (chain, profile) in &self.chains.profiles {
+            let minimums = [
+                ("min_liquidity_usd", profile.min_liquidity_usd),
+                ("min_volume_h24_usd", profile.min_volume_h24_usd),
+                ("min_mcap_usd", profile.min_mcap_usd),
+            ];
+            for (key, min) in minimums {
+                if let Some(min) = min {
+                    check(min >= 0.0, format!("chains.profiles.{}.{} must be >= 0 (got {})", chain, key, min));
+                }
+            }
+            if let Some(provider) = profile.security {
+                check(
+                    provider.supports(chain),
+                    format!("chains.profiles.{}.security: {} does not support chain {:?}", chain, provider, chain),
+                );
+            }
+            check(
+                profile.quote_tokens.iter().all(|q| !q.trim().is_empty()),
+                format!("chains.profiles.{}.quote_tokens must not contain empty entries", chain),
+            );
+            if let Some(symbol) = profile.quote_tokens.iter().find(|q| !q.trim().is_empty() && !is_token_address(q)) {
+                check(false, format!("chains.profiles.{}.quote_tokens must list mint addresses, not symbols (got {:?})", chain, symbol));
+            }
+        }
+
+        let mk = &self.markets;
+        let lists = [
+            ("dex_allow", &mk.dex_allow),
+            ("dex_deny", &mk.dex_deny),
+            ("quote_allow", &mk.quote_allow),
+            ("quote_deny", &mk.quote_deny),
+            ("native_quotes", &mk.native_quotes),
+            ("stable_quotes", &mk.stable_quotes),
+        ];
+        for (key, list) in lists {
+            check(list.iter().all(|t| !t.trim().is_empty()), format!("markets.{} must not contain empty entries", key));
+            if key.starts_with("dex_") {
+                continue;
+            }
+            if let Some(symbol) = list.iter().find(|t| !t.trim().is_empty() && !is_token_address(t)) {
+                check(false, format!("markets.{} must list mint addresses, not symbols (got {:?})", key, symbol));
+            }
+        }
+        let weights = [
+            ("native_quote_weight", mk.native_quote_weight),
+            ("stable_quote_weight", mk.stable_quote_weight),
+            ("other_quote_weight", mk.other_quote_weight),
+        ];
+        for (key, weight) in weights {
+            check(weight > 0.0 && weight <= 1.0, format!("markets.{} must be in (0, 1] (got {})", key, weight));
+        }
+
+        let n = &self.new_launch;
+        check(n.max_age_minutes > 0.0, format!("new_launch.max_age_minutes must be > 0 (got {})", n.max_age_minutes));
+        check(n.min_liquidity_usd >= 0.0, format!("new_launch.min_liquidity_usd must be >= 0 (got {})", n.min_liquidity_usd));
+        check(n.max_rugcheck_score >= 0, format!("new_launch.max_rugcheck_score must be >= 0 (got {})", n.max_rugcheck_score));
+
+        check(!self.queries.is_empty(), "queries must contain at least one search term".to_string());
+        check(
+            self.queries.iter().all(|q| !q.trim().is_empty()),
+            "queries must not contain empty search terms".to_string(),
+        );
+
+        let r = &self.rugcheck;
+        if let Some(max) = r.max_score {
+            check(max >= 0, format!("rugcheck.max_score must be >= 0 (got {})", max));
+        }
+        check(
+            r.deny_risks.iter().all(|name| !name.trim().is_empty()),
+            "rugcheck.deny_risks must not contain empty names".to_string(),
+        );
+
+        let p = &self.paper_trading;
+        check(p.buy_amount_sol > 0.0, format!("paper_trading.buy_amount_sol must be > 0 (got {})", p.buy_amount_sol));
+        check(p.take_profit_percent > 0.0, format!("paper_trading.take_profit_percent must be > 0 (got {})", p.take_profit_percent));
+        check(
+            p.stop_loss_percent > 0.0 && p.stop_loss_percent <= 100.0,
+            format!("paper_trading.stop_loss_percent must be in (0, 100] (got {})", p.stop_loss_percent),
+        );
+        check(!p.journal_path.trim().is_empty(), "paper_trading.journal_path must not be empty".to_string());
+        for (i, step) in p.take_profit_ladder.iter().enumerate() {
+            check(step.at_percent > 0.0, format!("paper_trading.take_profit_ladder[{}].at_percent must be > 0 (got {})", i, step.at_percent));
+            check(
+                step.sell_percent > 0.0 && step.sell_percent <= 100.0,
+                format!("paper_trading.take_profit_ladder[{}].sell_percent must be in (0, 100] (got {})", i, step.sell_percent),
+            );
+            if i > 0 {
+                check(
+                    step.at_percent > p.take_profit_ladder[i - 1].at_percent,
+                    format!("paper_trading.take_profit_ladder[{}].at_percent must be above the previous step's", i),
+                );
+            }
+        }
+        let ladder_total: f64 = p.take_profit_ladder.iter().map(|s| s.sell_percent).sum();
+        check(
+            ladder_total <= 100.0 + 1e-9,
+            format!("paper_trading.take_profit_ladder sells {}% in total, more than the whole position", ladder_total),
+        );
+        if let Some(trail) = p.trailing_stop_percent {
+            check(
+                trail > 0.0 && trail < 100.0,
+                format!("paper_trading.trailing_stop_percent must be in (0, 100) (got {})", trail),
+            );
+        }
+        check(p.max_hold_secs != Some(0), "paper_trading.max_hold_secs must be > 0".to_string());
+
+        let e = &self.execution;
+        check(
+            e.swap_fee_percent >= 0.0 && e.swap_fee_percent < 100.0,
+            format!("execution.swap_fee_percent must be in [0, 100) (got {})", e.swap_fee_percent),
+        );
+        check(e.priority_fee_sol >= 0.0, format!("execution.priority_fee_sol must be >= 0 (got {})", e.priority_fee_sol));
+        check(
+            e.slippage_tolerance_percent > 0.0,
+            format!("execution.slippage_tolerance_percent must be > 0 (got {})", e.slippage_tolerance_percent),
+        );
+        check(e.sol_price_usd > 0.0, format!("execution.sol_price_usd must be > 0 (got {})", e.sol_price_usd));
+
+        let pf = &self.portfolio;
+        check(
+            pf.starting_balance_sol > 0.0,
+            format!("portfolio.starting_balance_sol must be > 0 (got {})", pf.starting_balance_sol),
+        );
+        check(!pf.equity_path.trim().is_empty(), "portfolio.equity_path must not be empty".to_string());
+
+        let hc = &self.history;
+        check(hc.max_samples >= 2, format!("history.max_samples must be >= 2 (got {})", hc.max_samples));
+        check(
+            hc.liquidity_pull_percent > 0.0 && hc.liquidity_pull_percent <= 100.0,
+            format!("history.liquidity_pull_percent must be in (0, 100] (got {})", hc.liquidity_pull_percent),
+        );
+        check(
+            hc.volume_collapse_percent > 0.0 && hc.volume_collapse_percent <= 100.0,
+            format!("history.volume_collapse_percent must be in (0, 100] (got {})", hc.volume_collapse_percent),
+        );
+
+        let lw = &self.liquidity_watch;
+        check(
+            lw.drop_percent > 0.0 && lw.drop_percent <= 100.0,
+            format!("liquidity_watch.drop_percent must be in (0, 100] (got {})", lw.drop_percent),
+        );
+        check(lw.window_secs > 0, "liquidity_watch.window_secs must be > 0".to_string());
+
+        let a = &self.alerts;
+        check(a.realert_score_delta > 0.0, format!("alerts.realert_score_delta must be > 0 (got {})", a.realert_score_delta));
+
+        let m = &self.momentum;
+        check(m.min_volume_acceleration > 0.0, format!("momentum.min_volume_acceleration must be > 0 (got {})", m.min_volume_acceleration));
+        check(m.min_buy_sell_ratio > 0.0, format!("momentum.min_buy_sell_ratio must be > 0 (got {})", m.min_buy_sell_ratio));
+        check(
+            m.max_distribution_buy_sell_ratio > 0.0 && m.max_distribution_buy_sell_ratio < m.min_buy_sell_ratio,
+            format!(
+                "momentum.max_distribution_buy_sell_ratio must be > 0 and below momentum.min_buy_sell_ratio (got {})",
+                m.max_distribution_buy_sell_ratio
+            ),
+        );
+
+        let h = &self.http;
+        check(h.timeout_secs > 0, "http.timeout_secs must be > 0".to_string());
+        check(
+            h.base_backoff_ms <= h.max_backoff_ms,
+            format!("http.base_backoff_ms ({}) must be <= http.max_backoff_ms ({})", h.base_backoff_ms, h.max_backoff_ms),
+        );
+        check(h.default_rps > 0.0, format!("http.default_rps must be > 0 (got {})", h.default_rps));
+        for (host, rps) in &h.rate_limits {
+            check(*rps > 0.0, format!("http.rate_limits.\"{}\" must be > 0 (got {})", host, rps));
+        }
+
+        for (name, rule) in &self.rules {
+            check(rule.weight >= 0.0, format!("rules.{}.weight must be >= 0 (got {})", name, rule.weight));
+        }
+
+        for (i, signal) in self.signals.iter().enumerate() {
+            check(!signal.name.trim().is_empty(), format!("signals[{}].name must not be empty", i));
+            check(
+                self.signals[..i].iter().all(|s| s.name != signal.name),
+                format!("signals[{}].name {:?} is used more than once", i, signal.name),
+            );
+        }
+
+        let urls = [
+            ("api.dexscreener_url", Some(&self.api.dexscreener_url)),
+            ("api.rugcheck_url", Some(&self.api.rugcheck_url)),
+            ("api.telegram_url", self.api.telegram_url.as_ref()),
+        ];
+        for (key, url) in urls {
+            if let Some(url) = url {
+                check(
+                    url.starts_with("http://") || url.starts_with("https://"),
+                    format!("{} must be an http(s) URL (got {:?})", key, url),
+                );
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            bail!("invalid configuration:\n  - {}", errors.join("\n  - "))
+        }
+    }
+}
+
+/// Merges environment overrides into the parsed TOML before it is deserialized.
+///
+/// `DEXBOT_A__B=value` sets key `a.b`; the value is parsed as a TOML literal
+/// (`2000`, `true`, `["pump", "moon"]`) and falls back to a plain string,
+/// except for `STRING_KEYS`, which are never reinterpreted.
+/// `TELEGRAM_BOT_TOKEN` / `TELEGRAM_CHAT_ID` keep working as before.
+fn apply_env_overrides(table: &mut toml::Table, vars: impl Iterator<Item = (String, String)>) -> Result<()> {
+    for (key, raw) in vars {
+        let path: Vec<String> = match key.as_str() {
+            "TELEGRAM_BOT_TOKEN" => vec!["telegram".into(), "bot_token".into()],
+            "TELEGRAM_CHAT_ID" => vec!["telegram".into(), "chat_id".into()],
+            _ => match key.strip_prefix(ENV_PREFIX) {
+                Some(rest) if !rest.is_empty() => rest.split("__").map(str::to_lowercase).collect(),
+                _ => continue,
             },
+        };
+
+        let value = if STRING_KEYS.contains(&path.join(".").as_str()) {
+            toml::Value::String(raw)
+        } else {
+            format!("v = {}", raw)
+                .parse::<toml::Table>()
+                .ok()
+                .and_then(|mut t| t.remove("v"))
+                .unwrap_or(toml::Value::String(raw))
+        };
+
+        let (last, parents) = path.split_last().expect("override path is never empty");
+        let mut node = &mut *table;
+        for segment in parents {
+            node = node
+                .entry(segment.clone())
+                .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+                .as_table_mut()
+                .ok_or_else(|| anyhow!("{} overrides `{}`, which is not a table", key, segment))?;
+        }
+        node.insert(last.clone(), value);
+    }
+    Ok(())
+}
+
+/// Shared, atomically swappable configuration.
+///
+/// Readers take a snapshot with `current()` once per cycle, so a reload never
+/// changes settings halfway through a scan. Telegram credentials are read once
+/// at startup by the `Notifier` and are not affected by reloads.
+pub struct ConfigHandle {
+    current: RwLock<Arc<Config>>,
+    source: Option<PathBuf>,
+}
+
+impl ConfigHandle {
+    /// `source` is the file to re-read on reload; `None` means defaults plus environment overrides.
+    pub fn new(config: Config, source: Option<PathBuf>) -> Self {
+        Self {
+            current: RwLock::new(Arc::new(config)),
+            source,
+        }
+    }
+
+    pub fn current(&self) -> Arc<Config> {
+        Arc::clone(&self.current.read().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    /// Re-reads and validates the config; on success swaps it in and returns what changed.
+    /// On failure the running config is left untouched.
+    pub fn reload(&self) -> Result<Vec<String>> {
+        let new = match &self.source {
+            Some(path) => Config::load(path)?,
+            None => Config::from_env()?,
+        };
+        let changes = diff(&self.current(), &new);
+        if !changes.is_empty() {
+            *self.current.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(new);
         }
+        Ok(changes)
+    }
+
+    /// Reloads whenever the config file's modification time changes (checked every `poll`)
+    /// or, on Unix, when the process receives SIGHUP.
+    pub fn spawn_watcher(self: &Arc<Self>, poll: Duration) -> tokio::task::JoinHandle<()> {
+        let handle = Arc::clone(self);
+        // Taken before spawning, so an edit made before the task first runs is still seen.
+        let mut last_modified = handle.modified();
+        tokio::spawn(async move {
+            #[cfg(unix)]
+            let mut hangup = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup()).ok();
+            let mut ticker = tokio::time::interval(poll);
+
+            loop {
+                #[cfg(unix)]
+                let trigger = tokio::select! {
+                    _ = ticker.tick() => None,
+                    Some(_) = async { hangup.as_mut()?.recv().await } => Some("SIGHUP"),
+                };
+                #[cfg(not(unix))]
+                let trigger: Option<&str> = {
+                    ticker.tick().await;
+                    None
+                };
+
+                let modified = handle.modified();
+                let trigger = match trigger {
+                    Some(t) => t,
+                    None if modified != last_modified => "file change",
+                    None => continue,
+                };
+                last_modified = modified;
+
+                match handle.reload() {
+                    Ok(changes) if changes.is_empty() => log::info!("Config reloaded ({}): no changes", trigger),
+                    Ok(changes) => {
+                        println!("🔄 Config reloaded ({}):", trigger);
+                        for change in changes {
+                            println!("   {}", change);
+                        }
+                    }
+                    Err(e) => log::error!("Config reload ({}) rejected, keeping previous config: {:#}", trigger, e),
+                }
+            }
+        })
+    }
+
+    fn modified(&self) -> Option<SystemTime> {
+        let path = self.source.as_ref()?;
+        std::fs::metadata(path).and_then(|m| m.modified()).ok()
+    }
+}
+
+/// Lists every changed key as `key: old -> new`, with secrets masked.
+pub fn diff(old: &Config, new: &Config) -> Vec<String> {
+    let mut changes = Vec::new();
+    match (toml::Value::try_from(old), toml::Value::try_from(new)) {
+        (Ok(old), Ok(new)) => diff_values("", Some(&old), Some(&new), &mut changes),
+        _ => changes.push("<config could not be compared>".to_string()),
+    }
+    changes
+}
+
+fn diff_values(path: &str, old: Option<&toml::Value>, new: Option<&toml::Value>, out: &mut Vec<String>) {
+    if let (Some(toml::Value::Table(old)), Some(toml::Value::Table(new))) = (old, new) {
+        let mut keys: Vec<&String> = old.keys().chain(new.keys()).collect();
+        keys.sort();
+        keys.dedup();
+        for key in keys {
+            let child = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+            diff_values(&child, old.get(key), new.get(key), out);
+        }
+        return;
+    }
+    if old == new {
+        return;
     }
+    let show = |v: Option<&toml::Value>| match v {
+        None => "<unset>".to_string(),
+        Some(_) if SECRET_KEYS.contains(&path) => "***".to_string(),
+        Some(v) => v.to_string(),
+    };
+    out.push(format!("{}: {} -> {}", path, show(old), show(new)));
 }
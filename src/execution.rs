@@ -0,0 +1,74 @@
+use crate::config::ExecutionConfig;
+use crate::models::Pair;
+
+/// Wrapped SOL, the quote token of most Solana pools.
+pub const WSOL_MINT: &str = "So11111111111111111111111111111111111111112";
+
+/// A simulated swap: what a paper trade would actually have paid or received.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fill {
+    /// Effective USD price per token, after price impact and the swap fee.
+    pub price: f64,
+    /// Tokens bought or sold.
+    pub tokens: f64,
+    /// Constant-product price impact of the trade size, excluding fees; `None` without reserves.
+    pub impact_percent: Option<f64>,
+    /// Priority fee paid on top of the swap, in SOL.
+    pub fees_sol: f64,
+}
+
+impl Fill {
+    /// Whether the price impact exceeds `slippage_tolerance_percent`, i.e. a real swap would have reverted.
+    pub fn exceeds_tolerance(&self, config: &ExecutionConfig) -> bool {
+        config.enabled && self.impact_percent.is_some_and(|impact| impact > config.slippage_tolerance_percent)
+    }
+}
+
+/// USD per SOL: read off the pool when it is quoted in SOL, else `fallback`.
+pub fn sol_price_usd(pair: &Pair, fallback: f64) -> f64 {
+    // By mint only: anyone can name a token "SOL".
+    let sol_quoted = pair.quote_token.address == WSOL_MINT;
+    let native: Option<f64> = pair.price_native.parse().ok();
+    match (sol_quoted, pair.price(), native) {
+        (true, Some(usd), Some(native)) if native > 0.0 => usd / native,
+        _ => fallback,
+    }
+}
+
+/// Pool reserves `(base, quote)` when both are known.
+fn reserves(pair: &Pair) -> Option<(f64, f64)> {
+    let l = pair.liquidity.as_ref()?;
+    (l.base > 0.0 && l.quote > 0.0).then_some((l.base, l.quote))
+}
+
+/// Buying `amount_sol` worth of the base token. `None` when the pair has no price.
+///
+/// With reserves `x` (base) and `y` (quote), and `dx` the quote paid in after the swap fee `f`,
+/// a constant-product pool gives an average price `(1 + dx / y) / (1 - f)` times spot.
+pub fn buy(pair: &Pair, amount_sol: f64, sol_usd: f64, config: &ExecutionConfig) -> Option<Fill> {
+    let spot = pair.price().filter(|p| *p > 0.0)?;
+    let amount_usd = amount_sol * sol_usd;
+    if !config.enabled {
+        return Some(Fill { price: spot, tokens: amount_usd / spot, impact_percent: None, fees_sol: 0.0 });
+    }
+    let fee = config.swap_fee_percent / 100.0;
+    let quote_usd = pair.price_native.parse::<f64>().ok().filter(|n| *n > 0.0).map(|native| spot / native);
+    let impact = reserves(pair).zip(quote_usd).map(|((_, y), quote_usd)| amount_usd / quote_usd * (1.0 - fee) / y);
+    let price = spot * (1.0 + impact.unwrap_or(0.0)) / (1.0 - fee);
+    Some(Fill { price, tokens: amount_usd / price, impact_percent: impact.map(|i| i * 100.0), fees_sol: config.priority_fee_sol })
+}
+
+/// Selling `tokens` of the base token. `None` when the pair has no price.
+///
+/// With `dx = tokens * (1 - f)` paid in, the average price is `x * (1 - f) / (x + dx)` times spot.
+pub fn sell(pair: &Pair, tokens: f64, config: &ExecutionConfig) -> Option<Fill> {
+    let spot = pair.price().filter(|p| *p > 0.0)?;
+    if !config.enabled {
+        return Some(Fill { price: spot, tokens, impact_percent: None, fees_sol: 0.0 });
+    }
+    let fee = config.swap_fee_percent / 100.0;
+    let dx = tokens * (1.0 - fee);
+    let impact = reserves(pair).filter(|_| tokens > 0.0).map(|(x, _)| dx / (x + dx));
+    let price = spot * (1.0 - impact.unwrap_or(0.0)) * (1.0 - fee);
+    Some(Fill { price, tokens, impact_percent: impact.map(|i| i * 100.0), fees_sol: config.priority_fee_sol })
+}
@@ -0,0 +1,573 @@
+//! A small, type-checked expression language for describing signals in config, e.g.
+//!
+//! ```text
+//! liquidity.usd > 20000 && txns.h1.buys / txns.h1.sells > 1.5 && price_change.h1 in 10..80
+//! ```
+//!
+//! - Numbers, strings (`"raydium"`), `true` / `false`
+//! - Field paths into the pair and its rugcheck report (see `FIELDS`)
+//! - `+ - * /`, comparisons `== != < <= > >=`, `x in lo..hi` (inclusive), `! && ||`, parentheses
+//!
+//! Expressions are parsed and type-checked once by `Expr::compile`. At evaluation time a
+//! missing value (no liquidity, no rugcheck report, division by zero) makes any comparison
+//! involving it unknown. `!unknown` is still unknown, `&&` / `||` only need the known side
+//! when it settles the result (`false && unknown` is false), and an expression that ends up
+//! unknown does not match.
+
+use crate::history::PairDeltas;
+use crate::models::Pair;
+use crate::momentum::MomentumFeatures;
+use crate::rugcheck::RugCheckResponse;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Type {
+    Num,
+    Bool,
+    Str,
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Type::Num => "number",
+            Type::Bool => "bool",
+            Type::Str => "string",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Num(f64),
+    Bool(bool),
+    Str(String),
+}
+
+/// What an expression is evaluated against.
+pub struct EvalContext<'a> {
+    pub pair: &'a Pair,
+    pub rug_report: Option<&'a RugCheckResponse>,
+    /// Unix seconds, for `pair_age_minutes`.
+    pub now: i64,
+    /// Changes since the previous scan, for `history.*`.
+    pub deltas: Option<&'a PairDeltas>,
+}
+
+type Getter = fn(&EvalContext) -> Option<Value>;
+
+pub struct FieldDef {
+    pub path: &'static str,
+    pub ty: Type,
+    get: Getter,
+}
+
+fn num(v: f64) -> Option<Value> {
+    Some(Value::Num(v))
+}
+
+fn text(v: &str) -> Option<Value> {
+    Some(Value::Str(v.to_string()))
+}
+
+macro_rules! field {
+    ($path:literal, $ty:ident, $get:expr) => {
+        FieldDef { path: $path, ty: Type::$ty, get: $get }
+    };
+}
+
+/// Every field path an expression may reference.
+pub static FIELDS: &[FieldDef] = &[
+    field!("chain_id", Str, |c| text(&c.pair.chain_id)),
+    field!("dex_id", Str, |c| text(&c.pair.dex_id)),
+    field!("pair_address", Str, |c| text(&c.pair.pair_address)),
+    field!("base_token.address", Str, |c| text(&c.pair.base_token.address)),
+    field!("base_token.name", Str, |c| text(&c.pair.base_token.name)),
+    field!("base_token.symbol", Str, |c| text(&c.pair.base_token.symbol)),
+    field!("quote_token.address", Str, |c| text(&c.pair.quote_token.address)),
+    field!("quote_token.name", Str, |c| text(&c.pair.quote_token.name)),
+    field!("quote_token.symbol", Str, |c| text(&c.pair.quote_token.symbol)),
+    field!("price_usd", Num, |c| c.pair.price().map(Value::Num)),
+    field!("price_native", Num, |c| c.pair.price_native.parse().ok().map(Value::Num)),
+    field!("txns.m5.buys", Num, |c| num(c.pair.txns.m5.buys as f64)),
+    field!("txns.m5.sells", Num, |c| num(c.pair.txns.m5.sells as f64)),
+    field!("txns.h1.buys", Num, |c| num(c.pair.txns.h1.buys as f64)),
+    field!("txns.h1.sells", Num, |c| num(c.pair.txns.h1.sells as f64)),
+    field!("txns.h6.buys", Num, |c| num(c.pair.txns.h6.buys as f64)),
+    field!("txns.h6.sells", Num, |c| num(c.pair.txns.h6.sells as f64)),
+    field!("txns.h24.buys", Num, |c| num(c.pair.txns.h24.buys as f64)),
+    field!("txns.h24.sells", Num, |c| num(c.pair.txns.h24.sells as f64)),
+    field!("volume.m5", Num, |c| num(c.pair.volume.m5)),
+    field!("volume.h1", Num, |c| num(c.pair.volume.h1)),
+    field!("volume.h6", Num, |c| num(c.pair.volume.h6)),
+    field!("volume.h24", Num, |c| num(c.pair.volume.h24)),
+    field!("price_change.m5", Num, |c| c.pair.price_change.m5.map(Value::Num)),
+    field!("price_change.h1", Num, |c| c.pair.price_change.h1.map(Value::Num)),
+    field!("price_change.h6", Num, |c| c.pair.price_change.h6.map(Value::Num)),
+    field!("price_change.h24", Num, |c| c.pair.price_change.h24.map(Value::Num)),
+    field!("liquidity.usd", Num, |c| c.pair.liquidity_usd().map(Value::Num)),
+    field!("liquidity.base", Num, |c| c.pair.liquidity.as_ref().map(|l| Value::Num(l.base))),
+    field!("liquidity.quote", Num, |c| c.pair.liquidity.as_ref().map(|l| Value::Num(l.quote))),
+    field!("fdv", Num, |c| c.pair.fdv.map(Value::Num)),
+    field!("market_cap", Num, |c| c.pair.market_cap.map(Value::Num)),
+    field!("pair_created_at", Num, |c| c.pair.pair_created_at.map(|t| Value::Num(t as f64))),
+    field!("pair_age_minutes", Num, |c| c.pair.age_minutes(c.now).map(Value::Num)),
+    field!("momentum.volume_acceleration", Num, |c| MomentumFeatures::from_pair(c.pair).volume_acceleration.map(Value::Num)),
+    field!("momentum.buy_sell_ratio.m5", Num, |c| MomentumFeatures::from_pair(c.pair).buy_sell_ratio.m5.map(Value::Num)),
+    field!("momentum.buy_sell_ratio.h1", Num, |c| MomentumFeatures::from_pair(c.pair).buy_sell_ratio.h1.map(Value::Num)),
+    field!("momentum.buy_sell_ratio.h6", Num, |c| MomentumFeatures::from_pair(c.pair).buy_sell_ratio.h6.map(Value::Num)),
+    field!("momentum.buy_sell_ratio.h24", Num, |c| MomentumFeatures::from_pair(c.pair).buy_sell_ratio.h24.map(Value::Num)),
+    field!("momentum.positive_frames", Num, |c| num(MomentumFeatures::from_pair(c.pair).positive_frames as f64)),
+    field!("momentum.negative_frames", Num, |c| num(MomentumFeatures::from_pair(c.pair).negative_frames as f64)),
+    field!("history.samples", Num, |c| num(c.deltas.map_or(0, |d| d.samples) as f64)),
+    field!("history.elapsed_secs", Num, |c| c.deltas.map(|d| Value::Num(d.elapsed_secs as f64))),
+    field!("history.price_pct", Num, |c| c.deltas.and_then(|d| d.price_pct).map(Value::Num)),
+    field!("history.liquidity_pct", Num, |c| c.deltas.and_then(|d| d.liquidity_pct).map(Value::Num)),
+    field!("history.liquidity_from_peak_pct", Num, |c| c.deltas.and_then(|d| d.liquidity_from_peak_pct).map(Value::Num)),
+    field!("history.volume_h1_pct", Num, |c| c.deltas.and_then(|d| d.volume_h1_pct).map(Value::Num)),
+    field!("history.buys_h1_delta", Num, |c| c.deltas.map(|d| Value::Num(d.buys_h1_delta as f64))),
+    field!("history.sells_h1_delta", Num, |c| c.deltas.map(|d| Value::Num(d.sells_h1_delta as f64))),
+    field!("rugcheck.score", Num, |c| c.rug_report.map(|r| Value::Num(r.score as f64))),
+    field!("rugcheck.status", Str, |c| c.rug_report.map(|r| Value::Str(r.status.clone()))),
+    field!("rugcheck.creator", Str, |c| c.rug_report.and_then(|r| r.creator.clone()).map(Value::Str)),
+    field!("rugcheck.bundle_ratio", Num, |c| c.rug_report.and_then(|r| r.file_meta.as_ref()?.bundle_ratio).map(Value::Num)),
+    field!("rugcheck.risk_count", Num, |c| c.rug_report.map(|r| Value::Num(r.risks.len() as f64))),
+];
+
+/// A parse or type error, pointing at the offending column.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExprError {
+    pub source: String,
+    pub pos: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ExprError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let col = self.source[..self.pos.min(self.source.len())].chars().count();
+        write!(f, "{} (column {})\n    {}\n    {}^", self.message, col + 1, self.source, " ".repeat(col))
+    }
+}
+
+impl std::error::Error for ExprError {}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    And,
+    Or,
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Lit(Value),
+    Field(&'static FieldDef),
+    Not(Box<Node>),
+    Neg(Box<Node>),
+    Binary(BinOp, Box<Node>, Box<Node>),
+    InRange(Box<Node>, Box<Node>, Box<Node>),
+}
+
+impl fmt::Debug for FieldDef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.path)
+    }
+}
+
+/// A compiled, type-checked boolean expression.
+#[derive(Debug, Clone)]
+pub struct Expr {
+    source: String,
+    root: Node,
+}
+
+impl Expr {
+    pub fn compile(source: &str) -> Result<Self, ExprError> {
+        let tokens = lex(source)?;
+        let mut parser = Parser { source, tokens, pos: 0 };
+        let (root, ty) = parser.expr()?;
+        if let Some(tok) = parser.peek() {
+            return Err(parser.error_at(tok.pos, format!("unexpected `{}`", tok.kind)));
+        }
+        if ty != Type::Bool {
+            return Err(parser.error_at(0, format!("expression must be a condition (bool), but it is a {}", ty)));
+        }
+        Ok(Self { source: source.to_string(), root })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn matches(&self, ctx: &EvalContext) -> bool {
+        matches!(eval(&self.root, ctx), Some(Value::Bool(true)))
+    }
+}
+
+/// Stored in config as its source text; deserializing compiles it, so bad expressions fail the load.
+impl Serialize for Expr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.source)
+    }
+}
+
+impl<'de> Deserialize<'de> for Expr {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let source = String::deserialize(deserializer)?;
+        Expr::compile(&source).map_err(serde::de::Error::custom)
+    }
+}
+
+fn eval(node: &Node, ctx: &EvalContext) -> Option<Value> {
+    let as_num = |n: &Node| match eval(n, ctx) {
+        Some(Value::Num(v)) => Some(v),
+        _ => None,
+    };
+    let as_bool = |n: &Node| match eval(n, ctx) {
+        Some(Value::Bool(b)) => Some(b),
+        _ => None,
+    };
+
+    match node {
+        Node::Lit(v) => Some(v.clone()),
+        Node::Field(def) => (def.get)(ctx),
+        Node::Not(inner) => as_bool(inner).map(|b| Value::Bool(!b)),
+        Node::Neg(inner) => as_num(inner).map(|v| Value::Num(-v)),
+        Node::InRange(x, lo, hi) => {
+            let (x, lo, hi) = (as_num(x)?, as_num(lo)?, as_num(hi)?);
+            Some(Value::Bool(lo <= x && x <= hi))
+        }
+        Node::Binary(op, l, r) => match op {
+            BinOp::And => match (as_bool(l), as_bool(r)) {
+                (Some(false), _) | (_, Some(false)) => Some(Value::Bool(false)),
+                (Some(true), Some(true)) => Some(Value::Bool(true)),
+                _ => None,
+            },
+            BinOp::Or => match (as_bool(l), as_bool(r)) {
+                (Some(true), _) | (_, Some(true)) => Some(Value::Bool(true)),
+                (Some(false), Some(false)) => Some(Value::Bool(false)),
+                _ => None,
+            },
+            BinOp::Add | BinOp::Sub | BinOp::Mul | BinOp::Div => {
+                let (a, b) = (as_num(l)?, as_num(r)?);
+                let v = match op {
+                    BinOp::Add => a + b,
+                    BinOp::Sub => a - b,
+                    BinOp::Mul => a * b,
+                    _ if b == 0.0 => return None,
+                    _ => a / b,
+                };
+                Some(Value::Num(v))
+            }
+            _ => {
+                let ord = match (eval(l, ctx)?, eval(r, ctx)?) {
+                    (Value::Num(a), Value::Num(b)) => a.partial_cmp(&b),
+                    (Value::Str(a), Value::Str(b)) => Some(a.cmp(&b)),
+                    (Value::Bool(a), Value::Bool(b)) => Some(a.cmp(&b)),
+                    _ => None,
+                };
+                Some(Value::Bool(compare(*op, ord?)))
+            }
+        },
+    }
+}
+
+fn compare(op: BinOp, ord: std::cmp::Ordering) -> bool {
+    use std::cmp::Ordering::*;
+    match op {
+        BinOp::Eq => ord == Equal,
+        BinOp::Ne => ord != Equal,
+        BinOp::Lt => ord == Less,
+        BinOp::Le => ord != Greater,
+        BinOp::Gt => ord == Greater,
+        BinOp::Ge => ord != Less,
+        _ => false,
+    }
+}
+
+// =============================================================================
+// LEXER
+// =============================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokenKind {
+    Num(f64),
+    Str(String),
+    Ident(String),
+    Op(&'static str),
+}
+
+impl fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenKind::Num(n) => write!(f, "{}", n),
+            TokenKind::Str(s) => write!(f, "\"{}\"", s),
+            TokenKind::Ident(s) => f.write_str(s),
+            TokenKind::Op(s) => f.write_str(s),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    pos: usize,
+}
+
+const OPERATORS: &[&str] = &["&&", "||", "==", "!=", "<=", ">=", "..", "<", ">", "!", "+", "-", "*", "/", "(", ")"];
+
+fn lex(source: &str) -> Result<Vec<Token>, ExprError> {
+    let err = |pos: usize, message: String| ExprError { source: source.to_string(), pos, message };
+    let bytes = source.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let c = bytes[i] as char;
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() {
+            let start = i;
+            while i < bytes.len() && (bytes[i].is_ascii_digit() || bytes[i] == b'_' || (bytes[i] == b'.' && bytes.get(i + 1) != Some(&b'.'))) {
+                i += 1;
+            }
+            let text: String = source[start..i].chars().filter(|c| *c != '_').collect();
+            let n = text.parse::<f64>().map_err(|_| err(start, format!("invalid number `{}`", &source[start..i])))?;
+            tokens.push(Token { kind: TokenKind::Num(n), pos: start });
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let start = i;
+            while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_' || (bytes[i] == b'.' && bytes.get(i + 1) != Some(&b'.'))) {
+                i += 1;
+            }
+            tokens.push(Token { kind: TokenKind::Ident(source[start..i].to_string()), pos: start });
+        } else if c == '"' {
+            let start = i;
+            i += 1;
+            while i < bytes.len() && bytes[i] != b'"' {
+                i += 1;
+            }
+            if i >= bytes.len() {
+                return Err(err(start, "unterminated string".to_string()));
+            }
+            tokens.push(Token { kind: TokenKind::Str(source[start + 1..i].to_string()), pos: start });
+            i += 1;
+        } else if let Some(op) = OPERATORS.iter().find(|op| source[i..].starts_with(**op)) {
+            tokens.push(Token { kind: TokenKind::Op(op), pos: i });
+            i += op.len();
+        } else {
+            let ch = source[i..].chars().next().unwrap_or(c);
+            return Err(err(i, format!("unexpected character `{}`", ch)));
+        }
+    }
+    Ok(tokens)
+}
+
+// =============================================================================
+// PARSER / TYPE CHECKER
+// =============================================================================
+
+struct Parser<'a> {
+    source: &'a str,
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+type Typed = (Node, Type);
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn end_pos(&self) -> usize {
+        self.source.len()
+    }
+
+    fn error_at(&self, pos: usize, message: String) -> ExprError {
+        ExprError { source: self.source.to_string(), pos, message }
+    }
+
+    fn eat_op(&mut self, op: &str) -> Option<usize> {
+        match self.peek() {
+            Some(Token { kind: TokenKind::Op(o), pos }) if *o == op => {
+                let pos = *pos;
+                self.pos += 1;
+                Some(pos)
+            }
+            _ => None,
+        }
+    }
+
+    fn eat_keyword(&mut self, word: &str) -> bool {
+        if matches!(self.peek(), Some(Token { kind: TokenKind::Ident(w), .. }) if w == word) {
+            self.pos += 1;
+            return true;
+        }
+        false
+    }
+
+    fn expect(&self, pos: usize, ty: Type, want: Type, what: &str) -> Result<(), ExprError> {
+        if ty != want {
+            return Err(self.error_at(pos, format!("{} must be a {}, found {}", what, want, ty)));
+        }
+        Ok(())
+    }
+
+    fn here(&self) -> usize {
+        self.peek().map_or(self.end_pos(), |t| t.pos)
+    }
+
+    fn expr(&mut self) -> Result<Typed, ExprError> {
+        self.or()
+    }
+
+    fn or(&mut self) -> Result<Typed, ExprError> {
+        let start = self.here();
+        let (mut node, mut ty) = self.and()?;
+        while self.eat_op("||").is_some() {
+            self.expect(start, ty, Type::Bool, "left side of `||`")?;
+            let rhs_pos = self.here();
+            let (rhs, rty) = self.and()?;
+            self.expect(rhs_pos, rty, Type::Bool, "right side of `||`")?;
+            node = Node::Binary(BinOp::Or, Box::new(node), Box::new(rhs));
+            ty = Type::Bool;
+        }
+        Ok((node, ty))
+    }
+
+    fn and(&mut self) -> Result<Typed, ExprError> {
+        let start = self.here();
+        let (mut node, mut ty) = self.comparison()?;
+        while self.eat_op("&&").is_some() {
+            self.expect(start, ty, Type::Bool, "left side of `&&`")?;
+            let rhs_pos = self.here();
+            let (rhs, rty) = self.comparison()?;
+            self.expect(rhs_pos, rty, Type::Bool, "right side of `&&`")?;
+            node = Node::Binary(BinOp::And, Box::new(node), Box::new(rhs));
+            ty = Type::Bool;
+        }
+        Ok((node, ty))
+    }
+
+    fn comparison(&mut self) -> Result<Typed, ExprError> {
+        let start = self.here();
+        let (lhs, lty) = self.additive()?;
+
+        if self.eat_keyword("in") {
+            self.expect(start, lty, Type::Num, "left side of `in`")?;
+            let lo_pos = self.here();
+            let (lo, loty) = self.additive()?;
+            self.expect(lo_pos, loty, Type::Num, "range start")?;
+            if self.eat_op("..").is_none() {
+                return Err(self.error_at(self.here(), "expected `..` in range (e.g. `x in 10..80`)".to_string()));
+            }
+            let hi_pos = self.here();
+            let (hi, hity) = self.additive()?;
+            self.expect(hi_pos, hity, Type::Num, "range end")?;
+            return Ok((Node::InRange(Box::new(lhs), Box::new(lo), Box::new(hi)), Type::Bool));
+        }
+
+        let ops = [("==", BinOp::Eq), ("!=", BinOp::Ne), ("<=", BinOp::Le), (">=", BinOp::Ge), ("<", BinOp::Lt), (">", BinOp::Gt)];
+        for (sym, op) in ops {
+            if let Some(pos) = self.eat_op(sym) {
+                let (rhs, rty) = self.additive()?;
+                if lty != rty {
+                    return Err(self.error_at(pos, format!("cannot compare {} with {} using `{}`", lty, rty, sym)));
+                }
+                if lty == Type::Bool && !matches!(op, BinOp::Eq | BinOp::Ne) {
+                    return Err(self.error_at(pos, format!("`{}` needs numbers or strings, found bool", sym)));
+                }
+                return Ok((Node::Binary(op, Box::new(lhs), Box::new(rhs)), Type::Bool));
+            }
+        }
+        Ok((lhs, lty))
+    }
+
+    fn additive(&mut self) -> Result<Typed, ExprError> {
+        self.binary_level(&[("+", BinOp::Add), ("-", BinOp::Sub)], Self::multiplicative)
+    }
+
+    fn multiplicative(&mut self) -> Result<Typed, ExprError> {
+        self.binary_level(&[("*", BinOp::Mul), ("/", BinOp::Div)], Self::unary)
+    }
+
+    fn binary_level(&mut self, ops: &[(&str, BinOp)], next: fn(&mut Self) -> Result<Typed, ExprError>) -> Result<Typed, ExprError> {
+        let start = self.here();
+        let (mut node, ty) = next(self)?;
+        'outer: loop {
+            for (sym, op) in ops {
+                if self.eat_op(sym).is_some() {
+                    self.expect(start, ty, Type::Num, &format!("left side of `{}`", sym))?;
+                    let rhs_pos = self.here();
+                    let (rhs, rty) = next(self)?;
+                    self.expect(rhs_pos, rty, Type::Num, &format!("right side of `{}`", sym))?;
+                    node = Node::Binary(*op, Box::new(node), Box::new(rhs));
+                    continue 'outer;
+                }
+            }
+            return Ok((node, ty));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Typed, ExprError> {
+        if self.eat_op("!").is_some() {
+            let pos = self.here();
+            let (inner, ty) = self.unary()?;
+            self.expect(pos, ty, Type::Bool, "operand of `!`")?;
+            return Ok((Node::Not(Box::new(inner)), Type::Bool));
+        }
+        if self.eat_op("-").is_some() {
+            let pos = self.here();
+            let (inner, ty) = self.unary()?;
+            self.expect(pos, ty, Type::Num, "operand of `-`")?;
+            return Ok((Node::Neg(Box::new(inner)), Type::Num));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Typed, ExprError> {
+        let Some(tok) = self.peek().cloned() else {
+            return Err(self.error_at(self.end_pos(), "unexpected end of expression".to_string()));
+        };
+        self.pos += 1;
+        match tok.kind {
+            TokenKind::Num(n) => Ok((Node::Lit(Value::Num(n)), Type::Num)),
+            TokenKind::Str(s) => Ok((Node::Lit(Value::Str(s)), Type::Str)),
+            TokenKind::Ident(word) if word == "true" || word == "false" => Ok((Node::Lit(Value::Bool(word == "true")), Type::Bool)),
+            TokenKind::Ident(path) => match FIELDS.iter().find(|f| f.path == path) {
+                Some(def) => Ok((Node::Field(def), def.ty)),
+                None => Err(self.error_at(tok.pos, unknown_field_message(&path))),
+            },
+            TokenKind::Op("(") => {
+                let inner = self.expr()?;
+                if self.eat_op(")").is_none() {
+                    return Err(self.error_at(self.here(), "expected `)`".to_string()));
+                }
+                Ok(inner)
+            }
+            other => Err(self.error_at(tok.pos, format!("unexpected `{}`", other))),
+        }
+    }
+}
+
+fn unknown_field_message(path: &str) -> String {
+    let prefix = path.split('.').next().unwrap_or(path);
+    let related: Vec<&str> = FIELDS.iter().map(|f| f.path).filter(|p| p.starts_with(prefix)).collect();
+    if related.is_empty() {
+        let mut top: Vec<&str> = FIELDS.iter().map(|f| f.path.split('.').next().unwrap_or(f.path)).collect();
+        top.dedup();
+        format!("unknown field `{}`; available: {}", path, top.join(", "))
+    } else {
+        format!("unknown field `{}`; did you mean one of: {}", path, related.join(", "))
+    }
+}
@@ -0,0 +1,153 @@
+use crate::config::HistoryConfig;
+use crate::models::Pair;
+use anyhow::Result;
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+
+/// The metrics of one pair at one scan.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PairSample {
+    pub ts: i64,
+    pub price_usd: Option<f64>,
+    pub liquidity_usd: Option<f64>,
+    pub volume_m5: f64,
+    pub volume_h1: f64,
+    pub volume_h24: f64,
+    pub buys_h1: u32,
+    pub sells_h1: u32,
+}
+
+impl PairSample {
+    pub fn from_pair(pair: &Pair, ts: i64) -> Self {
+        Self {
+            ts,
+            price_usd: pair.price(),
+            liquidity_usd: pair.liquidity_usd(),
+            volume_m5: pair.volume.m5,
+            volume_h1: pair.volume.h1,
+            volume_h24: pair.volume.h24,
+            buys_h1: pair.txns.h1.buys,
+            sells_h1: pair.txns.h1.sells,
+        }
+    }
+}
+
+/// How a pair changed since its previous sample. Percentages are `None` when the earlier value is missing or zero.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PairDeltas {
+    /// Seconds since the previous sample.
+    pub elapsed_secs: i64,
+    /// Samples on record, not counting the current one.
+    pub samples: usize,
+    pub price_pct: Option<f64>,
+    pub liquidity_pct: Option<f64>,
+    pub volume_h1_pct: Option<f64>,
+    /// Change relative to the highest liquidity on record.
+    pub liquidity_from_peak_pct: Option<f64>,
+    pub buys_h1_delta: i64,
+    pub sells_h1_delta: i64,
+}
+
+fn pct(now: Option<f64>, before: Option<f64>) -> Option<f64> {
+    match (now, before) {
+        (Some(now), Some(before)) if before > 0.0 => Some((now - before) / before * 100.0),
+        _ => None,
+    }
+}
+
+/// Rolling per-pair-address history of scan samples, shared by the scanner and the analysis rules.
+///
+/// Rules see the history *before* the current scan is recorded, so deltas compare against the
+/// previous cycle. Optionally persisted to `history.path` so deltas survive restarts.
+pub struct PairHistory {
+    series: DashMap<String, VecDeque<PairSample>>,
+    max_samples: usize,
+    retention_secs: i64,
+    path: Option<PathBuf>,
+}
+
+impl Default for PairHistory {
+    fn default() -> Self {
+        Self::new(&HistoryConfig::default())
+    }
+}
+
+impl PairHistory {
+    /// Builds the history from `config`, preloading `config.path` if set.
+    pub fn new(config: &HistoryConfig) -> Self {
+        let history = Self {
+            series: DashMap::new(),
+            max_samples: config.max_samples,
+            retention_secs: config.retention_secs as i64,
+            path: config.path.as_ref().map(PathBuf::from),
+        };
+        if let Some(path) = &history.path {
+            match std::fs::read_to_string(path) {
+                Ok(contents) => match serde_json::from_str::<HashMap<String, VecDeque<PairSample>>>(&contents) {
+                    Ok(series) => {
+                        for (address, samples) in series {
+                            history.series.insert(address, samples);
+                        }
+                    }
+                    Err(e) => log::warn!("Ignoring unreadable pair history {}: {}", path.display(), e),
+                },
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => log::warn!("Could not read pair history {}: {}", path.display(), e),
+            }
+        }
+        history
+    }
+
+    /// Appends a sample for `pair`, dropping the oldest beyond `max_samples`.
+    pub fn record(&self, pair: &Pair, ts: i64) {
+        let mut series = self.series.entry(pair.pair_address.clone()).or_default();
+        series.push_back(PairSample::from_pair(pair, ts));
+        while series.len() > self.max_samples {
+            series.pop_front();
+        }
+    }
+
+    pub fn samples(&self, pair_address: &str) -> Vec<PairSample> {
+        self.series.get(pair_address).map(|s| s.iter().cloned().collect()).unwrap_or_default()
+    }
+
+    /// Compares `pair` as scanned at `now` against its most recent earlier sample.
+    pub fn deltas(&self, pair: &Pair, now: i64) -> Option<PairDeltas> {
+        let series = self.series.get(&pair.pair_address)?;
+        let prev = series.iter().rev().find(|s| s.ts <= now)?;
+        let current = PairSample::from_pair(pair, now);
+        let peak = series.iter().filter_map(|s| s.liquidity_usd).fold(None, |max: Option<f64>, l| Some(max.map_or(l, |m| m.max(l))));
+        Some(PairDeltas {
+            elapsed_secs: now - prev.ts,
+            samples: series.len(),
+            price_pct: pct(current.price_usd, prev.price_usd),
+            liquidity_pct: pct(current.liquidity_usd, prev.liquidity_usd),
+            volume_h1_pct: pct(Some(current.volume_h1), Some(prev.volume_h1)),
+            liquidity_from_peak_pct: pct(current.liquidity_usd, peak),
+            buys_h1_delta: current.buys_h1 as i64 - prev.buys_h1 as i64,
+            sells_h1_delta: current.sells_h1 as i64 - prev.sells_h1 as i64,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.series.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.series.is_empty()
+    }
+
+    /// Forgets pairs not seen within `retention_secs` of `now` and, if persistence is configured, writes the rest to disk.
+    pub fn save(&self, now: i64) -> Result<()> {
+        self.series.retain(|_, s| s.back().is_some_and(|last| now - last.ts < self.retention_secs));
+        let Some(path) = &self.path else { return Ok(()) };
+        let snapshot: HashMap<String, VecDeque<PairSample>> =
+            self.series.iter().map(|e| (e.key().clone(), e.value().clone())).collect();
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_string(&snapshot)?)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+}
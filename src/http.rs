@@ -0,0 +1,225 @@
+use crate::config::HttpConfig;
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use reqwest::{Client, StatusCode};
+use serde::de::DeserializeOwned;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
+
+/// Per-host request counters.
+#[derive(Debug, Default)]
+pub struct HostStats {
+    pub requests: AtomicU64,
+    pub throttled: AtomicU64,
+    pub retries: AtomicU64,
+    pub failed: AtomicU64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HostStatsSnapshot {
+    pub requests: u64,
+    pub throttled: u64,
+    pub retries: u64,
+    pub failed: u64,
+}
+
+struct Bucket {
+    tokens: f64,
+    capacity: f64,
+    per_sec: f64,
+    last_refill: Instant,
+}
+
+impl Bucket {
+    fn new(per_sec: f64) -> Self {
+        let capacity = per_sec.max(1.0);
+        Self {
+            tokens: capacity,
+            capacity,
+            per_sec,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Takes a token, or returns how long until one is available.
+    fn try_take(&mut self) -> Result<(), Duration> {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.per_sec).min(self.capacity);
+        self.last_refill = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - self.tokens) / self.per_sec))
+        }
+    }
+}
+
+/// Shared outbound HTTP layer: per-host token buckets, retries with exponential
+/// backoff and jitter (honoring `Retry-After`), and per-host counters.
+///
+/// One instance is shared by the DexScreener and Rugcheck clients and the Telegram notifier.
+pub struct HttpClient {
+    client: Client,
+    config: HttpConfig,
+    buckets: DashMap<String, Bucket>,
+    stats: DashMap<String, HostStats>,
+}
+
+impl HttpClient {
+    /// Fails when the TLS backend cannot be initialized.
+    pub fn new(config: &HttpConfig) -> Result<Self> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(config.timeout_secs))
+            .build()
+            .context("failed to build the HTTP client")?;
+        Ok(Self {
+            client,
+            config: config.clone(),
+            buckets: DashMap::new(),
+            stats: DashMap::new(),
+        })
+    }
+
+    /// Waits until the token bucket for `host` allows another request.
+    pub async fn acquire(&self, host: &str) {
+        let mut waited = false;
+        loop {
+            let wait = {
+                let per_sec = self.config.rate_limits.get(host).copied().unwrap_or(self.config.default_rps);
+                let mut bucket = self.buckets.entry(host.to_string()).or_insert_with(|| Bucket::new(per_sec));
+                match bucket.try_take() {
+                    Ok(()) => break,
+                    Err(wait) => wait,
+                }
+            };
+            if !waited {
+                self.stat(host, |s| &s.throttled);
+                waited = true;
+            }
+            sleep(wait).await;
+        }
+        self.stat(host, |s| &s.requests);
+    }
+
+    /// Delay before retry number `attempt` (0-based): `base * 2^attempt`, capped, with up
+    /// to half of it randomized. A server-provided `Retry-After` wins when present.
+    pub fn backoff(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+        if let Some(after) = retry_after {
+            return after.min(Duration::from_millis(self.config.max_backoff_ms));
+        }
+        let exp = self.config.base_backoff_ms.saturating_mul(1u64 << attempt.min(16));
+        let capped = exp.min(self.config.max_backoff_ms);
+        let jitter = RandomState::new().build_hasher().finish() % (capped / 2 + 1);
+        Duration::from_millis(capped / 2 + jitter)
+    }
+
+    pub fn max_retries(&self) -> u32 {
+        self.config.max_retries
+    }
+
+    pub fn note_retry(&self, host: &str) {
+        self.stat(host, |s| &s.retries);
+    }
+
+    pub fn note_failure(&self, host: &str) {
+        self.stat(host, |s| &s.failed);
+    }
+
+    /// GETs `url` and decodes the JSON body, retrying on network errors, 429 and 5xx.
+    pub async fn get_json<T: DeserializeOwned>(&self, url: &str) -> Result<T> {
+        let host = host_of(url);
+        let mut attempt = 0;
+        loop {
+            self.acquire(&host).await;
+            let (retry_after, error) = match self.client.get(url).send().await {
+                Ok(resp) if resp.status().is_success() => {
+                    return resp.json::<T>().await.map_err(|e| {
+                        self.note_failure(&host);
+                        anyhow!(e)
+                    });
+                }
+                Ok(resp) if is_retryable(resp.status()) => (retry_after(&resp), anyhow!("HTTP {}", resp.status())),
+                Ok(resp) => {
+                    self.note_failure(&host);
+                    return Err(anyhow!("HTTP {}", resp.status()));
+                }
+                Err(e) => (None, anyhow!(e)),
+            };
+
+            if attempt >= self.config.max_retries {
+                self.note_failure(&host);
+                return Err(error.context(format!("giving up after {} attempts", attempt + 1)));
+            }
+            let delay = self.backoff(attempt, retry_after);
+            log::debug!("{} failed ({}), retrying in {:?}", url, error, delay);
+            self.note_retry(&host);
+            sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    pub fn stats(&self) -> Vec<(String, HostStatsSnapshot)> {
+        let mut out: Vec<_> = self
+            .stats
+            .iter()
+            .map(|e| {
+                let s = e.value();
+                (
+                    e.key().clone(),
+                    HostStatsSnapshot {
+                        requests: s.requests.load(Ordering::Relaxed),
+                        throttled: s.throttled.load(Ordering::Relaxed),
+                        retries: s.retries.load(Ordering::Relaxed),
+                        failed: s.failed.load(Ordering::Relaxed),
+                    },
+                )
+            })
+            .collect();
+        out.sort_by(|a, b| a.0.cmp(&b.0));
+        out
+    }
+
+    fn stat(&self, host: &str, counter: impl Fn(&HostStats) -> &AtomicU64) {
+        let stats = self.stats.entry(host.to_string()).or_default();
+        counter(&stats).fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+fn is_retryable(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+fn retry_after(resp: &reqwest::Response) -> Option<Duration> {
+    parse_retry_after(resp.headers().get(reqwest::header::RETRY_AFTER)?.to_str().ok()?, Utc::now())
+}
+
+/// A `Retry-After` value, either delay-seconds (`120`) or an HTTP-date
+/// (`Wed, 21 Oct 2015 07:28:00 GMT`) relative to `now`. Dates in the past mean no wait.
+pub fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at = DateTime::parse_from_rfc2822(value).ok()?;
+    Some((at.with_timezone(&Utc) - now).to_std().unwrap_or(Duration::ZERO))
+}
+
+/// The `host[:port]` part of a URL, used as the rate-limit key.
+pub fn host_of(url: &str) -> String {
+    reqwest::Url::parse(url)
+        .ok()
+        .and_then(|u| {
+            let host = u.host_str()?.to_string();
+            Some(match u.port() {
+                Some(port) => format!("{}:{}", host, port),
+                None => host,
+            })
+        })
+        .unwrap_or_else(|| url.to_string())
+}
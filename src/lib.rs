@@ -0,0 +1,22 @@
+pub mod alerts;
+pub mod analysis;
+pub mod backtest;
+pub mod blacklist;
+pub mod client;
+pub mod config;
+pub mod execution;
+pub mod expr;
+pub mod history;
+pub mod http;
+pub mod models;
+pub mod momentum;
+#[cfg(feature = "mock")]
+pub mod mock;
+pub mod notifier;
+pub mod portfolio;
+pub mod rugcheck;
+pub mod rules;
+pub mod scanner;
+pub mod storage;
+pub mod trading;
+pub mod watcher;
@@ -0,0 +1,194 @@
+//! A fixture-driven stand-in for the DexScreener, Rugcheck and Telegram APIs,
+//! so the whole pipeline can run without network access.
+//!
+//! Requests are answered from JSON files under the fixture root:
+//!
+//! - `GET /latest/dex/search?q=pump` → `latest/dex/search/pump.json` (`q` is URL-decoded)
+//! - `GET /latest/dex/tokens/<addr>` → `latest/dex/tokens/<addr>.json`
+//! - `GET /v1/tokens/<addr>/report`  → `v1/tokens/<addr>/report.json`
+//! - `POST /bot<token>/<Method>`     → `telegram/<Method>.json`
+//!
+//! Responses set with `set_response` take precedence over files, and failures queued with
+//! `fail_next` take precedence over both. Anything else is a 404.
+
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    pub query: Option<String>,
+    pub body: String,
+}
+
+/// A queued error response: the HTTP status and an optional `Retry-After` header.
+#[derive(Debug, Clone)]
+struct Failure {
+    status: u16,
+    retry_after: Option<String>,
+}
+
+struct MockState {
+    root: PathBuf,
+    overrides: Mutex<HashMap<String, String>>,
+    failures: Mutex<HashMap<String, Vec<Failure>>>,
+    requests: Mutex<Vec<RecordedRequest>>,
+}
+
+pub struct MockServer {
+    addr: SocketAddr,
+    state: Arc<MockState>,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl MockServer {
+    /// Binds to `addr` (use port 0 for a random free port) and serves fixtures from `root`.
+    pub async fn start(root: impl Into<PathBuf>, addr: &str) -> Result<Self> {
+        let listener = TcpListener::bind(addr).await.with_context(|| format!("failed to bind mock server to {}", addr))?;
+        let addr = listener.local_addr()?;
+        let state = Arc::new(MockState {
+            root: root.into(),
+            overrides: Mutex::new(HashMap::new()),
+            failures: Mutex::new(HashMap::new()),
+            requests: Mutex::new(Vec::new()),
+        });
+
+        let task_state = Arc::clone(&state);
+        let task = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let state = Arc::clone(&task_state);
+                tokio::spawn(async move {
+                    if let Err(e) = handle(stream, &state).await {
+                        log::debug!("mock server connection error: {}", e);
+                    }
+                });
+            }
+        });
+
+        Ok(Self { addr, state, task })
+    }
+
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Serves `body` for the fixture key `key` (the fixture's relative path without `.json`,
+    /// e.g. `latest/dex/tokens/<addr>`), overriding any file on disk.
+    pub fn set_response(&self, key: &str, body: impl Into<String>) {
+        self.state.overrides.lock().unwrap().insert(key.trim_matches('/').to_string(), body.into());
+    }
+
+    /// Answers the next `times` requests for `key` with `status` (and `Retry-After: <retry_after>`
+    /// when given) before going back to the normal response.
+    pub fn fail_next(&self, key: &str, status: u16, retry_after: Option<&str>, times: usize) {
+        let failure = Failure { status, retry_after: retry_after.map(str::to_string) };
+        let mut failures = self.state.failures.lock().unwrap();
+        failures.entry(key.trim_matches('/').to_string()).or_default().extend(std::iter::repeat_n(failure, times));
+    }
+
+    /// Every request received so far, in arrival order.
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.state.requests.lock().unwrap().clone()
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Maps a request to its fixture key (relative path without the `.json` extension).
+fn fixture_key(path: &str, query: Option<&str>) -> String {
+    let path = path.trim_matches('/');
+    if let Some(rest) = path.strip_prefix("bot") {
+        let method = rest.rsplit('/').next().unwrap_or_default();
+        return format!("telegram/{}", method);
+    }
+    let q = query.and_then(|q| {
+        let url = reqwest::Url::parse(&format!("http://mock/?{}", q)).ok()?;
+        url.query_pairs().find(|(k, _)| k == "q").map(|(_, v)| v.into_owned())
+    });
+    match q {
+        Some(q) => format!("{}/{}", path, q),
+        None => path.to_string(),
+    }
+}
+
+fn read_fixture(root: &Path, key: &str) -> Option<String> {
+    if key.split('/').any(|segment| segment == "..") {
+        return None;
+    }
+    std::fs::read_to_string(root.join(format!("{}.json", key))).ok()
+}
+
+async fn handle(mut stream: TcpStream, state: &MockState) -> Result<()> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+    let header_end = loop {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Ok(());
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos + 4;
+        }
+    };
+
+    let head = String::from_utf8_lossy(&buf[..header_end]).to_string();
+    let mut lines = head.lines();
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let method = request_line.next().unwrap_or_default().to_string();
+    let target = request_line.next().unwrap_or_default().to_string();
+    let content_length = lines
+        .filter_map(|l| l.split_once(':'))
+        .find(|(k, _)| k.trim().eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, v)| v.trim().parse::<usize>().ok())
+        .unwrap_or(0);
+
+    while buf.len() < header_end + content_length {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+    let body = String::from_utf8_lossy(&buf[header_end..]).to_string();
+
+    let (path, query) = match target.split_once('?') {
+        Some((p, q)) => (p.to_string(), Some(q.to_string())),
+        None => (target.clone(), None),
+    };
+    let key = fixture_key(&path, query.as_deref());
+    state.requests.lock().unwrap().push(RecordedRequest { method, path, query, body });
+
+    let failure = state.failures.lock().unwrap().get_mut(&key).filter(|queue| !queue.is_empty()).map(|queue| queue.remove(0));
+    let found = state.overrides.lock().unwrap().get(&key).cloned();
+    let (status, body, extra) = match (failure, found.or_else(|| read_fixture(&state.root, &key))) {
+        (Some(failure), _) => {
+            let reason = reqwest::StatusCode::from_u16(failure.status).ok().and_then(|s| s.canonical_reason()).unwrap_or("Error");
+            let extra = failure.retry_after.map(|v| format!("Retry-After: {}\r\n", v)).unwrap_or_default();
+            (format!("{} {}", failure.status, reason), format!("{{\"error\":\"{}\"}}", reason), extra)
+        }
+        (None, Some(body)) => ("200 OK".to_string(), body, String::new()),
+        (None, None) => ("404 Not Found".to_string(), format!("{{\"error\":\"no fixture for {}\"}}", key), String::new()),
+    };
+
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n{}Connection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        extra,
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct DexScreenerResponse {
+    #[serde(default)]
     pub pairs: Vec<Pair>,
 }
 
@@ -10,10 +11,12 @@ pub struct DexScreenerResponse {
 pub struct Pair {
     pub chain_id: String,
     pub dex_id: String,
+    #[serde(default)]
     pub url: String,
     pub pair_address: String,
     pub base_token: Token,
     pub quote_token: Token,
+    #[serde(default)]
     pub price_native: String,
     pub price_usd: Option<String>,
     pub txns: TransactionStats,
@@ -25,6 +28,21 @@ pub struct Pair {
     pub pair_created_at: Option<i64>,
 }
 
+impl Pair {
+    pub fn price(&self) -> Option<f64> {
+        self.price_usd.as_ref().and_then(|p| p.parse::<f64>().ok())
+    }
+
+    /// Minutes since the pair was created, as of `now` (unix seconds).
+    pub fn age_minutes(&self, now: i64) -> Option<f64> {
+        self.pair_created_at.map(|ms| (now as f64 - ms as f64 / 1000.0).max(0.0) / 60.0)
+    }
+
+    pub fn liquidity_usd(&self) -> Option<f64> {
+        self.liquidity.as_ref().and_then(|l| l.usd)
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Token {
     pub address: String,
@@ -32,7 +50,10 @@ pub struct Token {
     pub symbol: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+// DexScreener omits frames it has no data for (and older snapshots only carried h24),
+// so every frame falls back to zero rather than failing the whole pair.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
 pub struct TransactionStats {
     pub m5: TxFrames,
     pub h1: TxFrames,
@@ -40,13 +61,14 @@ pub struct TransactionStats {
     pub h24: TxFrames,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct TxFrames {
     pub buys: u32,
     pub sells: u32,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
 pub struct VolumeStats {
     pub m5: f64,
     pub h1: f64,
@@ -54,7 +76,7 @@ pub struct VolumeStats {
     pub h24: f64,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct PriceChangeStats {
     pub m5: Option<f64>,
     pub h1: Option<f64>,
@@ -65,6 +87,8 @@ pub struct PriceChangeStats {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct LiquidityStats {
     pub usd: Option<f64>,
+    #[serde(default)]
     pub base: f64,
+    #[serde(default)]
     pub quote: f64,
 }
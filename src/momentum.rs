@@ -0,0 +1,62 @@
+use crate::models::{Pair, TxFrames};
+use serde::{Deserialize, Serialize};
+
+/// A value per DexScreener timeframe.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Frames<T> {
+    pub m5: T,
+    pub h1: T,
+    pub h6: T,
+    pub h24: T,
+}
+
+impl<T: Copy> Frames<T> {
+    /// Shortest frame first.
+    pub fn to_array(&self) -> [T; 4] {
+        [self.m5, self.h1, self.h6, self.h24]
+    }
+}
+
+/// Cross-timeframe features derived from one pair snapshot.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MomentumFeatures {
+    /// Last 5 minutes of volume extrapolated to an hour, relative to the last hour's volume.
+    /// Above 1 means trading is speeding up.
+    pub volume_acceleration: Option<f64>,
+    /// Buys per sell in each frame; `None` when the frame has no sells.
+    pub buy_sell_ratio: Frames<Option<f64>>,
+    /// How many of the m5/h1/h6/h24 price changes are positive / negative.
+    pub positive_frames: usize,
+    pub negative_frames: usize,
+}
+
+fn ratio(tx: &TxFrames) -> Option<f64> {
+    (tx.sells > 0).then(|| tx.buys as f64 / tx.sells as f64)
+}
+
+impl MomentumFeatures {
+    pub fn from_pair(pair: &Pair) -> Self {
+        let volume_acceleration = (pair.volume.h1 > 0.0).then(|| pair.volume.m5 * 12.0 / pair.volume.h1);
+        let txns = &pair.txns;
+        let buy_sell_ratio = Frames {
+            m5: ratio(&txns.m5),
+            h1: ratio(&txns.h1),
+            h6: ratio(&txns.h6),
+            h24: ratio(&txns.h24),
+        };
+        let pc = &pair.price_change;
+        let changes = [pc.m5, pc.h1, pc.h6, pc.h24];
+        Self {
+            volume_acceleration,
+            buy_sell_ratio,
+            positive_frames: changes.iter().filter(|c| c.is_some_and(|c| c > 0.0)).count(),
+            negative_frames: changes.iter().filter(|c| c.is_some_and(|c| c < 0.0)).count(),
+        }
+    }
+
+    /// Buy pressure rising into the present: the m5 buy/sell ratio is at least h1's, and h1's at least h6's.
+    pub fn buy_pressure_rising(&self) -> bool {
+        let r = self.buy_sell_ratio.to_array();
+        r[..3].windows(2).all(|w| matches!((w[0], w[1]), (Some(short), Some(long)) if short >= long))
+    }
+}
@@ -0,0 +1,67 @@
+use crate::config::Config;
+use crate::http::{host_of, HttpClient};
+use std::sync::Arc;
+use teloxide::prelude::*;
+use teloxide::types::ParseMode;
+use teloxide::RequestError;
+
+const TELEGRAM_API_URL: &str = "https://api.telegram.org";
+
+/// Sends alerts to the configured Telegram chat. A missing or invalid chat id turns it into a no-op.
+///
+/// Sends go through the shared `HttpClient` rate limiter and are retried on flood
+/// control (`RetryAfter`) and network errors.
+#[derive(Clone)]
+pub struct Notifier {
+    bot: Bot,
+    chat_id: Option<ChatId>,
+    http: Arc<HttpClient>,
+    host: String,
+}
+
+impl Notifier {
+    pub fn new(config: &Config, http: Arc<HttpClient>) -> Self {
+        let chat_id = config.telegram.chat_id.parse().ok().filter(|id| *id != 0).map(ChatId);
+        let mut bot = Bot::new(&config.telegram.bot_token);
+        let mut host = host_of(TELEGRAM_API_URL);
+        if let Some(url) = &config.api.telegram_url {
+            match reqwest::Url::parse(url) {
+                Ok(url) => {
+                    host = host_of(url.as_str());
+                    bot = bot.set_api_url(url);
+                }
+                Err(e) => log::warn!("Ignoring invalid api.telegram_url {}: {}", url, e),
+            }
+        }
+        Self { bot, chat_id, http, host }
+    }
+
+    pub async fn send(&self, msg: String) {
+        let Some(chat_id) = self.chat_id else { return };
+        let mut attempt = 0;
+        loop {
+            self.http.acquire(&self.host).await;
+            let retry_after = match self.bot.send_message(chat_id, msg.clone()).parse_mode(ParseMode::MarkdownV2).await {
+                Ok(_) => return,
+                Err(RequestError::RetryAfter(after)) => Some(after),
+                Err(RequestError::Network(e)) if attempt < self.http.max_retries() => {
+                    log::debug!("Telegram network error: {}", e);
+                    None
+                }
+                Err(e) => {
+                    self.http.note_failure(&self.host);
+                    log::warn!("Telegram send failed: {}", e);
+                    return;
+                }
+            };
+            if attempt >= self.http.max_retries() {
+                self.http.note_failure(&self.host);
+                log::warn!("Telegram send failed: still rate limited after {} attempts", attempt + 1);
+                return;
+            }
+            self.http.note_retry(&self.host);
+            tokio::time::sleep(self.http.backoff(attempt, retry_after)).await;
+            attempt += 1;
+        }
+    }
+}
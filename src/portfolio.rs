@@ -0,0 +1,134 @@
+use crate::client::DexScreenerClient;
+use crate::config::{lists_token, Config};
+use crate::execution::WSOL_MINT;
+use crate::models::Pair;
+use crate::trading::{ActiveTrade, ClosedTrade};
+use serde::{Deserialize, Serialize};
+use std::sync::RwLock;
+
+/// SOL not tied up in positions: the starting balance plus closed PnL, less what is still staked
+/// in open positions (net of their partial exits) and the fees they paid.
+pub fn cash_sol(starting_sol: f64, active: &[ActiveTrade], closed: &[ClosedTrade]) -> f64 {
+    let closed_pnl: f64 = closed.iter().map(|t| t.pnl_sol()).sum();
+    starting_sol + closed_pnl + active.iter().map(|t| t.proceeds_sol() - t.amount_sol - t.fees_sol).sum::<f64>()
+}
+
+/// The paper portfolio at one moment, derived from the open and closed trades. Open positions and
+/// equity are valued at the current SOL price; realized USD uses the SOL price of each fill, and
+/// unrealized USD weighs today's value against the cost at entry.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PortfolioSnapshot {
+    pub ts: i64,
+    pub sol_usd: f64,
+    pub starting_sol: f64,
+    /// SOL not tied up in positions.
+    pub cash_sol: f64,
+    /// What is still held, marked at the last price seen.
+    pub positions_sol: f64,
+    pub equity_sol: f64,
+    pub equity_usd: f64,
+    /// Closed trades, partial exits and every priority fee paid.
+    pub realized_pnl_sol: f64,
+    pub realized_pnl_usd: f64,
+    pub unrealized_pnl_sol: f64,
+    pub unrealized_pnl_usd: f64,
+    pub open_positions: usize,
+}
+
+impl PortfolioSnapshot {
+    pub fn compute(starting_sol: f64, active: &[ActiveTrade], closed: &[ClosedTrade], sol_usd: f64, ts: i64) -> Self {
+        let cash_sol = cash_sol(starting_sol, active, closed);
+        let positions_sol: f64 = active.iter().map(|t| t.value_sol(t.mark_price(), sol_usd)).sum();
+        let realized_pnl_sol = closed.iter().map(|t| t.pnl_sol()).sum::<f64>() + active.iter().map(|t| t.realized_sol()).sum::<f64>();
+        let realized_pnl_usd = closed.iter().map(|t| t.pnl_usd(sol_usd)).sum::<f64>() + active.iter().map(|t| t.realized_usd(sol_usd)).sum::<f64>();
+        let unrealized_pnl_sol: f64 = active.iter().map(|t| t.value_sol(t.mark_price(), sol_usd) - t.remaining() * t.amount_sol).sum();
+        let unrealized_pnl_usd: f64 = active.iter().map(|t| t.unrealized_usd(t.mark_price(), sol_usd)).sum();
+        Self {
+            ts,
+            sol_usd,
+            starting_sol,
+            cash_sol,
+            positions_sol,
+            equity_sol: cash_sol + positions_sol,
+            equity_usd: (cash_sol + positions_sol) * sol_usd,
+            realized_pnl_sol,
+            realized_pnl_usd,
+            unrealized_pnl_sol,
+            unrealized_pnl_usd,
+            open_positions: active.len(),
+        }
+    }
+
+    /// e.g. `equity 10.1200 SOL ($1518.00), cash 9.9000 SOL, realized +0.0200 SOL, unrealized +0.1000 SOL, 1 open`.
+    pub fn describe(&self) -> String {
+        format!(
+            "equity {:.4} SOL (${:.2}), cash {:.4} SOL, realized {:+.4} SOL (${:+.2}), unrealized {:+.4} SOL (${:+.2}), {} open",
+            self.equity_sol,
+            self.equity_usd,
+            self.cash_sol,
+            self.realized_pnl_sol,
+            self.realized_pnl_usd,
+            self.unrealized_pnl_sol,
+            self.unrealized_pnl_usd,
+            self.open_positions
+        )
+    }
+}
+
+/// SOL/USD from DexScreener: the deepest pool pairing wrapped SOL with a `markets.stable_quotes` token.
+#[derive(Default)]
+pub struct SolPriceFeed {
+    /// `(price, fetched at)`.
+    latest: RwLock<Option<(f64, i64)>>,
+}
+
+impl SolPriceFeed {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The last price fetched, if any.
+    pub fn latest(&self) -> Option<f64> {
+        self.latest.read().unwrap_or_else(|e| e.into_inner()).map(|(price, _)| price)
+    }
+
+    /// The last price fetched, else `execution.sol_price_usd`.
+    pub fn price_or_fallback(&self, config: &Config) -> f64 {
+        self.latest().unwrap_or(config.execution.sol_price_usd)
+    }
+
+    /// Fetches a new price unless the last one is younger than `portfolio.sol_price_ttl_secs`.
+    /// Failures keep the previous price.
+    pub async fn refresh(&self, client: &DexScreenerClient, config: &Config, now: i64) -> Option<f64> {
+        if let Some((price, at)) = *self.latest.read().unwrap_or_else(|e| e.into_inner()) {
+            if now - at < config.portfolio.sol_price_ttl_secs as i64 {
+                return Some(price);
+            }
+        }
+        let price = match client.get_token_pairs(WSOL_MINT).await {
+            Ok(data) => Self::price_from_pairs(&data.pairs, config),
+            Err(e) => {
+                log::warn!("SOL price refresh failed: {}", e);
+                None
+            }
+        };
+        match price {
+            Some(price) => {
+                *self.latest.write().unwrap_or_else(|e| e.into_inner()) = Some((price, now));
+                Some(price)
+            }
+            None => self.latest(),
+        }
+    }
+
+    /// The price of the deepest wrapped-SOL pool whose quote token's mint address is in
+    /// `markets.stable_quotes`; a look-alike token reusing a stable's symbol never matches.
+    pub fn price_from_pairs(pairs: &[Pair], config: &Config) -> Option<f64> {
+        pairs
+            .iter()
+            .filter(|p| p.base_token.address == WSOL_MINT && lists_token(&config.markets.stable_quotes, &p.quote_token))
+            .filter_map(|p| Some((p.price()?, p.liquidity_usd().unwrap_or(0.0))))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(price, _)| price)
+    }
+}
@@ -1,14 +1,27 @@
+use crate::config::{HttpConfig, RugcheckConfig};
+use crate::http::HttpClient;
 use serde::{Deserialize, Serialize};
-use anyhow::{Result, anyhow};
-use reqwest::Client;
-use std::time::Duration;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use dashmap::DashMap;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+pub const RUGCHECK_API_URL: &str = "https://api.rugcheck.xyz/v1";
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct RugCheckResponse {
+    #[serde(default)]
     pub score: i32,
     pub status: String,
+    #[serde(default)]
     pub risks: Vec<Risk>,
     pub file_meta: Option<FileMeta>,
+    /// Wallet that created the token.
+    #[serde(default)]
+    pub creator: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -25,32 +38,162 @@ pub struct FileMeta {
     pub bundle_ratio: Option<f64>,
 }
 
+impl RugCheckResponse {
+    pub fn is_good(&self) -> bool {
+        self.status == "good"
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct CachedReport {
+    fetched_at: i64,
+    report: RugCheckResponse,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: usize,
+}
+
+/// Rugcheck reports keyed by mint address. "good" reports expire after `good_ttl_secs`,
+/// everything else after `risky_ttl_secs`; a TTL of 0 disables caching for that class.
+pub struct ReportCache {
+    entries: DashMap<String, CachedReport>,
+    good_ttl_secs: i64,
+    risky_ttl_secs: i64,
+    path: Option<PathBuf>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl ReportCache {
+    /// Builds the cache from `config`, preloading unexpired entries from `config.cache_path` if set.
+    pub fn new(config: &RugcheckConfig) -> Self {
+        let cache = Self {
+            entries: DashMap::new(),
+            good_ttl_secs: config.cache_good_ttl_secs as i64,
+            risky_ttl_secs: config.cache_risky_ttl_secs as i64,
+            path: config.cache_path.as_ref().map(PathBuf::from),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        };
+        if let Some(path) = &cache.path {
+            match std::fs::read_to_string(path) {
+                Ok(contents) => match serde_json::from_str::<HashMap<String, CachedReport>>(&contents) {
+                    Ok(entries) => {
+                        let now = Utc::now().timestamp();
+                        for (mint, entry) in entries {
+                            if cache.is_fresh(&entry, now) {
+                                cache.entries.insert(mint, entry);
+                            }
+                        }
+                    }
+                    Err(e) => log::warn!("Ignoring unreadable rugcheck cache {}: {}", path.display(), e),
+                },
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => log::warn!("Could not read rugcheck cache {}: {}", path.display(), e),
+            }
+        }
+        cache
+    }
+
+    fn ttl(&self, report: &RugCheckResponse) -> i64 {
+        if report.is_good() { self.good_ttl_secs } else { self.risky_ttl_secs }
+    }
+
+    fn is_fresh(&self, entry: &CachedReport, now: i64) -> bool {
+        now - entry.fetched_at < self.ttl(&entry.report)
+    }
+
+    pub fn get(&self, mint: &str) -> Option<RugCheckResponse> {
+        let now = Utc::now().timestamp();
+        let fresh = self.entries.get(mint).filter(|e| self.is_fresh(e, now)).map(|e| e.report.clone());
+        match fresh {
+            Some(report) => {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                Some(report)
+            }
+            None => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+        }
+    }
+
+    pub fn insert(&self, mint: &str, report: RugCheckResponse) {
+        if self.ttl(&report) > 0 {
+            let entry = CachedReport { fetched_at: Utc::now().timestamp(), report };
+            self.entries.insert(mint.to_string(), entry);
+        }
+    }
+
+    /// Drops expired entries and, if persistence is configured, writes the rest to disk.
+    pub fn save(&self) -> Result<()> {
+        let now = Utc::now().timestamp();
+        self.entries.retain(|_, e| self.is_fresh(e, now));
+        let Some(path) = &self.path else { return Ok(()) };
+        let snapshot: HashMap<String, CachedReport> =
+            self.entries.iter().map(|e| (e.key().clone(), e.value().clone())).collect();
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_string(&snapshot)?)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries: self.entries.len(),
+        }
+    }
+}
+
 pub struct RugCheckClient {
-    client: Client,
+    http: Arc<HttpClient>,
     api_url: String,
+    cache: Option<ReportCache>,
 }
 
 impl RugCheckClient {
-    pub fn new() -> Self {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(10))
-            .build()
-            .unwrap();
+    pub fn new() -> Result<Self> {
+        Self::with_base_url(RUGCHECK_API_URL)
+    }
+
+    /// A client with its own `HttpClient` using the default `[http]` settings.
+    pub fn with_base_url(api_url: &str) -> Result<Self> {
+        Ok(Self::with_http(api_url, Arc::new(HttpClient::new(&HttpConfig::default())?)))
+    }
+
+    pub fn with_http(api_url: &str, http: Arc<HttpClient>) -> Self {
         Self {
-            client,
-            api_url: "https://api.rugcheck.xyz/v1".to_string(),
+            http,
+            api_url: api_url.trim_end_matches('/').to_string(),
+            cache: None,
         }
     }
 
+    pub fn with_cache(mut self, cache: ReportCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    pub fn cache(&self) -> Option<&ReportCache> {
+        self.cache.as_ref()
+    }
+
+    /// Returns a cached report while it is fresh, otherwise fetches and caches a new one.
     pub async fn scan_token(&self, address: &str) -> Result<RugCheckResponse> {
+        if let Some(report) = self.cache.as_ref().and_then(|c| c.get(address)) {
+            return Ok(report);
+        }
         let url = format!("{}/tokens/{}/report", self.api_url, address);
-        let resp = self.client.get(&url).send().await?;
-        
-        if resp.status().is_success() {
-            let data = resp.json::<RugCheckResponse>().await?;
-            Ok(data)
-        } else {
-            Err(anyhow!("Rugcheck API error: {}", resp.status()))
+        let report: RugCheckResponse = self.http.get_json(&url).await.context("Rugcheck API error")?;
+        if let Some(cache) = &self.cache {
+            cache.insert(address, report.clone());
         }
+        Ok(report)
     }
 }
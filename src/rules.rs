@@ -0,0 +1,714 @@
+use crate::analysis::{Finding, MarketPattern};
+use crate::blacklist::BlacklistStore;
+use crate::config::{lists_token, Config, SecurityProvider};
+use crate::expr::EvalContext;
+use crate::history::PairDeltas;
+use crate::models::Pair;
+use crate::momentum::MomentumFeatures;
+use crate::rugcheck::{Risk, RugCheckResponse};
+use std::sync::Arc;
+
+/// Everything a rule may look at for one pair.
+pub struct RuleContext<'a> {
+    pub pair: &'a Pair,
+    pub config: &'a Config,
+    pub rug_report: Option<&'a RugCheckResponse>,
+    /// Evaluation time in unix seconds: wall clock when scanning, snapshot time when backtesting.
+    pub now: i64,
+    /// Changes since the pair's previous scan, when the engine has a `PairHistory` and the pair was seen before.
+    pub deltas: Option<&'a PairDeltas>,
+}
+
+/// A single analysis check. Rules are evaluated in registration order and report
+/// zero or more findings; see `FindingKind` for how findings classify a pair.
+///
+/// Implement this in your own crate and `RuleRegistry::register` it to add checks
+/// without forking. Each rule can be disabled or re-weighted under `[rules.<name>]`.
+pub trait Rule: Send + Sync {
+    /// Stable identifier, used as the config key.
+    fn name(&self) -> &str;
+
+    fn evaluate(&self, ctx: &RuleContext) -> Vec<Finding>;
+}
+
+/// Ordered set of rules run by the `AnalysisEngine`.
+pub struct RuleRegistry {
+    rules: Vec<Box<dyn Rule>>,
+}
+
+impl Default for RuleRegistry {
+    fn default() -> Self {
+        Self::with_defaults()
+    }
+}
+
+impl RuleRegistry {
+    pub fn empty() -> Self {
+        Self { rules: Vec::new() }
+    }
+
+    /// The built-in checks, in priority order: rejections first, then filters, then signals.
+    pub fn with_defaults() -> Self {
+        let mut registry = Self::empty();
+        registry
+            .register(Blacklist::default())
+            .register(RugcheckStatus)
+            .register(RugcheckRisks)
+            .register(BundledSupply)
+            .register(FakeVolume)
+            .register(LiquidityPull)
+            .register(VolumeCollapse)
+            .register(Chain)
+            .register(Market)
+            .register(QuoteNormalization)
+            .register(MinLiquidity)
+            .register(MinVolume)
+            .register(MinMarketCap)
+            .register(PairAge)
+            .register(RugDump)
+            .register(Distribution)
+            .register(Momentum)
+            .register(EarlyAccumulation)
+            .register(Pump)
+            .register(Tier1)
+            .register(CustomSignals)
+            .register(NewLaunch);
+        registry
+    }
+
+    /// Appends `rule`; a rule with the same name replaces the existing one in place.
+    pub fn register(&mut self, rule: impl Rule + 'static) -> &mut Self {
+        match self.rules.iter().position(|r| r.name() == rule.name()) {
+            Some(idx) => self.rules[idx] = Box::new(rule),
+            None => self.rules.push(Box::new(rule)),
+        }
+        self
+    }
+
+    pub fn remove(&mut self, name: &str) -> &mut Self {
+        self.rules.retain(|r| r.name() != name);
+        self
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.rules.iter().map(|r| r.name()).collect()
+    }
+
+    /// Runs every enabled rule and scales each finding's impact by the rule's configured weight.
+    pub fn evaluate(&self, ctx: &RuleContext) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        for rule in &self.rules {
+            let settings = ctx.config.rules.get(rule.name());
+            if settings.is_some_and(|s| !s.enabled) {
+                continue;
+            }
+            let weight = settings.map_or(1.0, |s| s.weight);
+            findings.extend(rule.evaluate(ctx).into_iter().map(|mut f| {
+                f.impact *= weight;
+                f
+            }));
+        }
+        findings
+    }
+}
+
+// =============================================================================
+// BUILT-IN RULES
+// =============================================================================
+
+/// Rejects pairs whose base mint, pair address or creator wallet is blacklisted,
+/// with one finding per list that matched.
+#[derive(Default)]
+pub struct Blacklist {
+    store: Arc<BlacklistStore>,
+}
+
+impl Blacklist {
+    /// Checks `store` in addition to `[blacklist]` in the config.
+    pub fn new(store: Arc<BlacklistStore>) -> Self {
+        Self { store }
+    }
+}
+
+impl Rule for Blacklist {
+    fn name(&self) -> &str {
+        "blacklist"
+    }
+
+    fn evaluate(&self, ctx: &RuleContext) -> Vec<Finding> {
+        self.store
+            .check(ctx.pair, ctx.rug_report, ctx.config)
+            .into_iter()
+            .map(|hit| Finding::reject(self.name(), MarketPattern::Blacklisted, -100.0, format!("{} {} is on the {} blacklist", hit.kind.subject(), hit.address, hit.kind)))
+            .collect()
+    }
+}
+
+pub struct RugcheckStatus;
+
+impl Rule for RugcheckStatus {
+    fn name(&self) -> &str {
+        "rugcheck_status"
+    }
+
+    fn evaluate(&self, ctx: &RuleContext) -> Vec<Finding> {
+        match ctx.rug_report {
+            Some(report) if ctx.config.rugcheck.only_good && !report.is_good() => vec![Finding::reject(
+                self.name(),
+                MarketPattern::RugcheckRisk,
+                -60.0,
+                match report.risks.is_empty() {
+                    true => format!("status \"{}\" is not \"good\"", report.status),
+                    false => format!("status \"{}\" is not \"good\" ({})", report.status, risk_names(report.risks.iter())),
+                },
+            )
+            .measured(report.score as f64, None)],
+            _ => vec![],
+        }
+    }
+}
+
+/// Applies the `[rugcheck]` risk policies: aggregate score cap, denied risk names and per-level caps.
+/// Each violated policy is its own finding naming the risks that failed it.
+pub struct RugcheckRisks;
+
+impl Rule for RugcheckRisks {
+    fn name(&self) -> &str {
+        "rugcheck_risks"
+    }
+
+    fn evaluate(&self, ctx: &RuleContext) -> Vec<Finding> {
+        let Some(report) = ctx.rug_report else { return vec![] };
+        let policy = &ctx.config.rugcheck;
+        let mut findings = Vec::new();
+
+        if let Some(max) = policy.max_score.filter(|max| report.score > *max) {
+            findings.push(
+                Finding::reject(self.name(), MarketPattern::RugcheckRisk, -30.0, format!("risk score {} > max {}", report.score, max))
+                    .measured(report.score as f64, Some(max as f64)),
+            );
+        }
+
+        for denied in &policy.deny_risks {
+            let needle = denied.to_lowercase();
+            for risk in report.risks.iter().filter(|r| r.name.to_lowercase().contains(&needle)) {
+                findings.push(
+                    Finding::reject(
+                        self.name(),
+                        MarketPattern::RugcheckRisk,
+                        -30.0,
+                        format!("denied risk \"{}\" ({}, score {})", risk.name, risk.level, risk.score),
+                    )
+                    .measured(risk.score as f64, None),
+                );
+            }
+        }
+
+        for (level, max) in &policy.max_risks_per_level {
+            let at_level: Vec<_> = report.risks.iter().filter(|r| r.level.eq_ignore_ascii_case(level)).collect();
+            let count = at_level.len();
+            if count > *max {
+                findings.push(
+                    Finding::reject(
+                        self.name(),
+                        MarketPattern::RugcheckRisk,
+                        -20.0,
+                        format!("{} \"{}\" risks > max {}: {}", count, level, max, risk_names(at_level.into_iter())),
+                    )
+                    .measured(count as f64, Some(*max as f64)),
+                );
+            }
+        }
+
+        findings
+    }
+}
+
+fn risk_names<'a>(risks: impl Iterator<Item = &'a Risk>) -> String {
+    risks.map(|r| r.name.as_str()).collect::<Vec<_>>().join(", ")
+}
+
+/// Rejects pairs whose rugcheck bundle ratio exceeds `filters.max_bundled_supply_percent`.
+/// Pairs without a report, or whose report has no bundle ratio, pass.
+pub struct BundledSupply;
+
+impl Rule for BundledSupply {
+    fn name(&self) -> &str {
+        "bundled_supply"
+    }
+
+    fn evaluate(&self, ctx: &RuleContext) -> Vec<Finding> {
+        let max = ctx.config.filters.max_bundled_supply_percent;
+        let ratio = ctx.rug_report.and_then(|r| r.file_meta.as_ref()).and_then(|m| m.bundle_ratio);
+        match ratio.map(|r| r * 100.0) {
+            Some(bundled) if bundled > max => vec![Finding::reject(
+                self.name(),
+                MarketPattern::BundledSupply,
+                -40.0,
+                format!("{:.1}% of supply bundled > max {:.1}%", bundled, max),
+            )
+            .measured(bundled, Some(max))],
+            _ => vec![],
+        }
+    }
+}
+
+/// Wash trading shows up as 24h volume far beyond the pool's liquidity.
+pub struct FakeVolume;
+
+impl Rule for FakeVolume {
+    fn name(&self) -> &str {
+        "fake_volume"
+    }
+
+    fn evaluate(&self, ctx: &RuleContext) -> Vec<Finding> {
+        let max = ctx.config.filters.max_vlr;
+        match ctx.pair.liquidity_usd().filter(|l| *l > 0.0) {
+            Some(liq) if ctx.pair.volume.h24 / liq > max => {
+                let vlr = ctx.pair.volume.h24 / liq;
+                vec![Finding::reject(self.name(), MarketPattern::FakeVolume, -40.0, format!("VLR {:.2} > max {:.2}", vlr, max))
+                    .measured(vlr, Some(max))]
+            }
+            _ => vec![],
+        }
+    }
+}
+
+/// Deltas against the previous sample, if it is recent enough to compare with.
+fn recent_deltas<'a>(ctx: &RuleContext<'a>) -> Option<&'a PairDeltas> {
+    ctx.deltas.filter(|d| d.elapsed_secs <= ctx.config.history.max_gap_secs as i64)
+}
+
+pub struct LiquidityPull;
+
+impl Rule for LiquidityPull {
+    fn name(&self) -> &str {
+        "liquidity_pull"
+    }
+
+    fn evaluate(&self, ctx: &RuleContext) -> Vec<Finding> {
+        let max_drop = ctx.config.history.liquidity_pull_percent;
+        match recent_deltas(ctx).and_then(|d| Some((d.liquidity_pct?, d.elapsed_secs))) {
+            Some((change, elapsed)) if change <= -max_drop => vec![Finding::reject(
+                self.name(),
+                MarketPattern::LiquidityPull,
+                -80.0,
+                format!("liquidity {:+.1}% in {}s (pull threshold -{:.0}%)", change, elapsed, max_drop),
+            )
+            .measured(change, Some(-max_drop))],
+            _ => vec![],
+        }
+    }
+}
+
+pub struct VolumeCollapse;
+
+impl Rule for VolumeCollapse {
+    fn name(&self) -> &str {
+        "volume_collapse"
+    }
+
+    fn evaluate(&self, ctx: &RuleContext) -> Vec<Finding> {
+        let max_drop = ctx.config.history.volume_collapse_percent;
+        match recent_deltas(ctx).and_then(|d| Some((d.volume_h1_pct?, d.elapsed_secs))) {
+            Some((change, elapsed)) if change <= -max_drop => vec![Finding::reject(
+                self.name(),
+                MarketPattern::VolumeCollapse,
+                -30.0,
+                format!("h1 volume {:+.1}% in {}s (collapse threshold -{:.0}%)", change, elapsed, max_drop),
+            )
+            .measured(change, Some(-max_drop))],
+            _ => vec![],
+        }
+    }
+}
+
+/// Filters pairs on chains outside `chains.allow`, or quoted in a token the chain's profile doesn't list.
+pub struct Chain;
+
+impl Rule for Chain {
+    fn name(&self) -> &str {
+        "chain"
+    }
+
+    fn evaluate(&self, ctx: &RuleContext) -> Vec<Finding> {
+        let chain = &ctx.pair.chain_id;
+        if !ctx.config.chain_allowed(chain) {
+            return vec![Finding::filter(self.name(), -50.0, format!("chain {} is not in chains.allow", chain))];
+        }
+        let Some(profile) = ctx.config.chains.profiles.get(chain).filter(|p| !p.quote_tokens.is_empty()) else {
+            return vec![];
+        };
+        let quote = &ctx.pair.quote_token;
+        match lists_token(&profile.quote_tokens, quote) {
+            true => vec![],
+            false => vec![Finding::filter(self.name(), -20.0, format!("quote token {} is not whitelisted on {}", quote.symbol, chain))],
+        }
+    }
+}
+
+/// Filters pairs on DEXes or in quote tokens outside `[markets]`'s allow lists, or on its deny lists.
+pub struct Market;
+
+impl Rule for Market {
+    fn name(&self) -> &str {
+        "market"
+    }
+
+    fn evaluate(&self, ctx: &RuleContext) -> Vec<Finding> {
+        let m = &ctx.config.markets;
+        let (dex, quote) = (&ctx.pair.dex_id, &ctx.pair.quote_token);
+        let listed = |list: &[String]| list.iter().any(|d| d.eq_ignore_ascii_case(dex));
+        let mut findings = Vec::new();
+        if listed(&m.dex_deny) {
+            findings.push(Finding::filter(self.name(), -30.0, format!("dex {} is denied", dex)));
+        } else if !m.dex_allow.is_empty() && !listed(&m.dex_allow) {
+            findings.push(Finding::filter(self.name(), -20.0, format!("dex {} is not in markets.dex_allow", dex)));
+        }
+        if lists_token(&m.quote_deny, quote) {
+            findings.push(Finding::filter(self.name(), -30.0, format!("quote token {} is denied", quote.symbol)));
+        } else if !m.quote_allow.is_empty() && !lists_token(&m.quote_allow, quote) {
+            findings.push(Finding::filter(self.name(), -20.0, format!("quote token {} is not in markets.quote_allow", quote.symbol)));
+        }
+        findings
+    }
+}
+
+/// Weighs liquidity and 24h volume by quote kind (`markets.*_quote_weight`): a pool quoted in a
+/// volatile token is notionally worth less than its USD figure. Notes the normalized values when the
+/// weight is below 1, and filters pairs that only clear `min_liquidity` / `min_volume_h24` before weighing.
+pub struct QuoteNormalization;
+
+impl Rule for QuoteNormalization {
+    fn name(&self) -> &str {
+        "quote_normalization"
+    }
+
+    fn evaluate(&self, ctx: &RuleContext) -> Vec<Finding> {
+        let m = &ctx.config.markets;
+        let quote = &ctx.pair.quote_token;
+        let kind = m.quote_kind(quote);
+        let weight = m.quote_weight(kind);
+        if weight >= 1.0 {
+            return vec![];
+        }
+        let filters = ctx.config.filters_for(&ctx.pair.chain_id);
+        let liq = ctx.pair.liquidity_usd().unwrap_or(0.0);
+        let volume = ctx.pair.volume.h24;
+        let mut findings = vec![Finding::note(
+            self.name(),
+            format!(
+                "quote {} ({}, x{:.2}): liquidity ${:.0} -> ${:.0}, 24h volume ${:.0} -> ${:.0}",
+                quote.symbol,
+                kind,
+                weight,
+                liq,
+                liq * weight,
+                volume,
+                volume * weight
+            ),
+        )
+        .measured(weight, None)];
+        if liq >= filters.min_liquidity_usd && liq * weight < filters.min_liquidity_usd {
+            findings.push(
+                Finding::filter(self.name(), -15.0, format!("normalized liquidity ${:.0} < min ${:.0}", liq * weight, filters.min_liquidity_usd))
+                    .measured(liq * weight, Some(filters.min_liquidity_usd)),
+            );
+        }
+        if volume >= filters.min_volume_h24_usd && volume * weight < filters.min_volume_h24_usd {
+            findings.push(
+                Finding::filter(self.name(), -10.0, format!("normalized 24h volume ${:.0} < min ${:.0}", volume * weight, filters.min_volume_h24_usd))
+                    .measured(volume * weight, Some(filters.min_volume_h24_usd)),
+            );
+        }
+        findings
+    }
+}
+
+pub struct MinLiquidity;
+
+impl Rule for MinLiquidity {
+    fn name(&self) -> &str {
+        "min_liquidity"
+    }
+
+    fn evaluate(&self, ctx: &RuleContext) -> Vec<Finding> {
+        let min = ctx.config.filters_for(&ctx.pair.chain_id).min_liquidity_usd;
+        match (&ctx.pair.liquidity, ctx.pair.liquidity_usd()) {
+            (_, Some(liq)) if liq < min => {
+                vec![Finding::filter(self.name(), -20.0, format!("liquidity ${:.0} < min ${:.0}", liq, min)).measured(liq, Some(min))]
+            }
+            (Some(_), None) => vec![Finding::filter(self.name(), -20.0, "liquidity in USD unknown".to_string())],
+            _ => vec![],
+        }
+    }
+}
+
+pub struct MinVolume;
+
+impl Rule for MinVolume {
+    fn name(&self) -> &str {
+        "min_volume_h24"
+    }
+
+    fn evaluate(&self, ctx: &RuleContext) -> Vec<Finding> {
+        let (volume, min) = (ctx.pair.volume.h24, ctx.config.filters_for(&ctx.pair.chain_id).min_volume_h24_usd);
+        if volume < min {
+            return vec![Finding::filter(self.name(), -15.0, format!("24h volume ${:.0} < min ${:.0}", volume, min)).measured(volume, Some(min))];
+        }
+        vec![]
+    }
+}
+
+pub struct MinMarketCap;
+
+impl Rule for MinMarketCap {
+    fn name(&self) -> &str {
+        "min_mcap"
+    }
+
+    fn evaluate(&self, ctx: &RuleContext) -> Vec<Finding> {
+        let min = ctx.config.filters_for(&ctx.pair.chain_id).min_mcap_usd;
+        match ctx.pair.market_cap {
+            Some(mcap) if mcap < min => {
+                vec![Finding::filter(self.name(), -10.0, format!("market cap ${:.0} < min ${:.0}", mcap, min)).measured(mcap, Some(min))]
+            }
+            _ => vec![],
+        }
+    }
+}
+
+pub struct PairAge;
+
+impl Rule for PairAge {
+    fn name(&self) -> &str {
+        "pair_age"
+    }
+
+    fn evaluate(&self, ctx: &RuleContext) -> Vec<Finding> {
+        let f = &ctx.config.filters;
+        if f.min_pair_age_minutes.is_none() && f.max_pair_age_minutes.is_none() {
+            return vec![];
+        }
+        let Some(age) = ctx.pair.age_minutes(ctx.now) else {
+            return vec![Finding::filter(self.name(), -10.0, "pair creation time unknown".to_string())];
+        };
+        match (f.min_pair_age_minutes, f.max_pair_age_minutes) {
+            (Some(min), _) if age < min => {
+                vec![Finding::filter(self.name(), -10.0, format!("pair age {:.0}m < min {:.0}m", age, min)).measured(age, Some(min))]
+            }
+            (_, Some(max)) if age > max => {
+                vec![Finding::filter(self.name(), -10.0, format!("pair age {:.0}m > max {:.0}m", age, max)).measured(age, Some(max))]
+            }
+            _ => vec![],
+        }
+    }
+}
+
+pub struct RugDump;
+
+impl Rule for RugDump {
+    fn name(&self) -> &str {
+        "rug_dump"
+    }
+
+    fn evaluate(&self, ctx: &RuleContext) -> Vec<Finding> {
+        match ctx.pair.price_change.m5 {
+            Some(m5) if m5 < -50.0 => vec![Finding::signal(self.name(), MarketPattern::RugCandidate, -50.0, format!("5m price change {:.1}% < -50%", m5))
+                .measured(m5, Some(-50.0))],
+            _ => vec![],
+        }
+    }
+}
+
+/// Steady short-term momentum is the only pattern the paper trader enters on.
+pub struct Momentum;
+
+impl Rule for Momentum {
+    fn name(&self) -> &str {
+        "m5_momentum"
+    }
+
+    fn evaluate(&self, ctx: &RuleContext) -> Vec<Finding> {
+        match ctx.pair.price_change.m5 {
+            Some(m5) if m5 > 5.0 && m5 < 50.0 => vec![Finding::signal(self.name(), MarketPattern::GoodCandidate, 20.0, format!("5m price change {:.1}% within 5%..50%", m5))
+                .measured(m5, Some(5.0))],
+            _ => vec![],
+        }
+    }
+}
+
+/// Volume accelerating and buyers increasingly dominant across m5/h1/h6 while the price is
+/// still flat-to-modestly up on every frame: accumulation ahead of a move.
+pub struct EarlyAccumulation;
+
+impl Rule for EarlyAccumulation {
+    fn name(&self) -> &str {
+        "early_accumulation"
+    }
+
+    fn evaluate(&self, ctx: &RuleContext) -> Vec<Finding> {
+        let cfg = &ctx.config.momentum;
+        let features = MomentumFeatures::from_pair(ctx.pair);
+        let (Some(accel), Some(m5_ratio), Some(h1_ratio)) = (features.volume_acceleration, features.buy_sell_ratio.m5, features.buy_sell_ratio.h1) else {
+            return vec![];
+        };
+        let h1_change = ctx.pair.price_change.h1.unwrap_or(0.0);
+        if accel >= cfg.min_volume_acceleration
+            && m5_ratio.min(h1_ratio) >= cfg.min_buy_sell_ratio
+            && features.buy_pressure_rising()
+            && features.negative_frames == 0
+            && h1_change <= cfg.max_accumulation_h1_change
+        {
+            return vec![Finding::signal(
+                self.name(),
+                MarketPattern::EarlyAccumulation,
+                15.0,
+                format!("volume {:.1}x h1 run-rate, buys/sells {:.2} (m5) vs {:.2} (h1), h1 price {:+.1}%", accel, m5_ratio, h1_ratio, h1_change),
+            )
+            .measured(accel, Some(cfg.min_volume_acceleration))];
+        }
+        vec![]
+    }
+}
+
+/// After a run-up on the longer frames, sellers outnumber buyers on the short frames
+/// while volume holds up: holders are exiting.
+pub struct Distribution;
+
+impl Rule for Distribution {
+    fn name(&self) -> &str {
+        "distribution"
+    }
+
+    fn evaluate(&self, ctx: &RuleContext) -> Vec<Finding> {
+        let cfg = &ctx.config.momentum;
+        let features = MomentumFeatures::from_pair(ctx.pair);
+        let (Some(h24_change), Some(m5_ratio), Some(h1_ratio)) = (ctx.pair.price_change.h24, features.buy_sell_ratio.m5, features.buy_sell_ratio.h1) else {
+            return vec![];
+        };
+        if h24_change >= cfg.min_distribution_h24_change
+            && m5_ratio.max(h1_ratio) <= cfg.max_distribution_buy_sell_ratio
+            && features.volume_acceleration.is_some_and(|a| a >= 1.0)
+        {
+            return vec![Finding::signal(
+                self.name(),
+                MarketPattern::Distribution,
+                -25.0,
+                format!("h24 price {:+.1}% but buys/sells {:.2} (m5) and {:.2} (h1)", h24_change, m5_ratio, h1_ratio),
+            )
+            .measured(m5_ratio.max(h1_ratio), Some(cfg.max_distribution_buy_sell_ratio))];
+        }
+        vec![]
+    }
+}
+
+pub struct Pump;
+
+impl Rule for Pump {
+    fn name(&self) -> &str {
+        "m5_pump"
+    }
+
+    fn evaluate(&self, ctx: &RuleContext) -> Vec<Finding> {
+        match ctx.pair.price_change.m5 {
+            Some(m5) if m5 > 20.0 => vec![Finding::signal(self.name(), MarketPattern::PumpCandidate, 10.0, format!("5m price change {:.1}% > 20%", m5))
+                .measured(m5, Some(20.0))],
+            _ => vec![],
+        }
+    }
+}
+
+pub struct Tier1;
+
+impl Rule for Tier1 {
+    fn name(&self) -> &str {
+        "tier1"
+    }
+
+    fn evaluate(&self, ctx: &RuleContext) -> Vec<Finding> {
+        let pair = ctx.pair;
+        match (pair.market_cap, pair.liquidity_usd()) {
+            (Some(mcap), Some(liq)) if mcap > 10_000_000.0 && liq > 500_000.0 && pair.volume.h24 > 1_000_000.0 => {
+                vec![Finding::signal(self.name(), MarketPattern::StableTier1, 15.0, format!("market cap ${:.0} with ${:.0} liquidity", mcap, liq))
+                    .measured(mcap, Some(10_000_000.0))]
+            }
+            _ => vec![],
+        }
+    }
+}
+
+/// Evaluates the `[[signals]]` expressions from config, in order.
+pub struct CustomSignals;
+
+impl Rule for CustomSignals {
+    fn name(&self) -> &str {
+        "custom_signals"
+    }
+
+    fn evaluate(&self, ctx: &RuleContext) -> Vec<Finding> {
+        let eval_ctx = EvalContext { pair: ctx.pair, rug_report: ctx.rug_report, now: ctx.now, deltas: ctx.deltas };
+        ctx.config
+            .signals
+            .iter()
+            .filter(|s| s.enabled && s.when.matches(&eval_ctx))
+            .map(|s| Finding::signal(&s.name, MarketPattern::Custom(s.name.clone()), s.impact, format!("matched `{}`", s.when.source())))
+            .collect()
+    }
+}
+
+/// New-launch mode: young pairs must clear stricter liquidity and rugcheck bars, and otherwise
+/// classify as `NewLaunch` unless an earlier signal already named them.
+pub struct NewLaunch;
+
+impl Rule for NewLaunch {
+    fn name(&self) -> &str {
+        "new_launch"
+    }
+
+    fn evaluate(&self, ctx: &RuleContext) -> Vec<Finding> {
+        let cfg = &ctx.config.new_launch;
+        let Some(age) = ctx.pair.age_minutes(ctx.now).filter(|age| cfg.enabled && *age <= cfg.max_age_minutes) else {
+            return vec![];
+        };
+        let mut findings = Vec::new();
+
+        let min_liq = cfg.min_liquidity_usd.max(ctx.config.filters_for(&ctx.pair.chain_id).min_liquidity_usd);
+        let liq = ctx.pair.liquidity_usd().unwrap_or(0.0);
+        if liq < min_liq {
+            findings.push(
+                Finding::filter(self.name(), -20.0, format!("new launch ({:.0}m old) liquidity ${:.0} < min ${:.0}", age, liq, min_liq))
+                    .measured(liq, Some(min_liq)),
+            );
+        }
+
+        // Only chains whose security provider runs can be expected to produce a report.
+        let checked = ctx.config.security_provider(&ctx.pair.chain_id) != SecurityProvider::Disabled;
+        match ctx.rug_report {
+            None if cfg.require_rugcheck && checked => findings.push(Finding::reject(
+                self.name(),
+                MarketPattern::RugcheckRisk,
+                -30.0,
+                format!("new launch ({:.0}m old) without a rugcheck report", age),
+            )),
+            Some(report) if report.score > cfg.max_rugcheck_score => findings.push(
+                Finding::reject(
+                    self.name(),
+                    MarketPattern::RugcheckRisk,
+                    -30.0,
+                    format!("new launch ({:.0}m old) risk score {} > max {}", age, report.score, cfg.max_rugcheck_score),
+                )
+                .measured(report.score as f64, Some(cfg.max_rugcheck_score as f64)),
+            ),
+            _ => {}
+        }
+
+        if findings.is_empty() {
+            findings.push(
+                Finding::signal(self.name(), MarketPattern::NewLaunch, 10.0, format!("launched {:.0}m ago", age))
+                    .measured(age, Some(cfg.max_age_minutes)),
+            );
+        }
+        findings
+    }
+}
@@ -0,0 +1,283 @@
+use crate::alerts::{AlertDecision, AlertGate};
+use crate::analysis::{AnalysisEngine, AnalysisReport, MarketPattern};
+use crate::blacklist::BlacklistStore;
+use crate::client::DexScreenerClient;
+use crate::config::{Config, SecurityProvider};
+use crate::history::PairHistory;
+use crate::http::HttpClient;
+use crate::models::Pair;
+use crate::notifier::Notifier;
+use crate::rugcheck::{ReportCache, RugCheckClient};
+use crate::rules;
+use crate::storage::{Database, SnapshotRecord};
+use crate::trading::PaperTradingEngine;
+use anyhow::Result;
+use chrono::Utc;
+use teloxide::utils::markdown::{escape, escape_link_url};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// A pair and every query that returned it.
+#[derive(Debug, Clone)]
+pub struct MergedPair {
+    pub pair: Pair,
+    pub queries: Vec<String>,
+}
+
+/// The pairs returned by one cycle's queries, merged by pair address so each is analyzed once.
+/// When several queries return a pair, the most recently fetched data wins.
+#[derive(Debug, Default)]
+pub struct ScanBatch {
+    pairs: Vec<MergedPair>,
+    index: HashMap<String, usize>,
+    fetched: usize,
+}
+
+impl ScanBatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, query: &str, pairs: Vec<Pair>) {
+        for pair in pairs {
+            self.fetched += 1;
+            match self.index.get(&pair.pair_address) {
+                Some(&i) => {
+                    let merged = &mut self.pairs[i];
+                    merged.pair = pair;
+                    if !merged.queries.iter().any(|q| q == query) {
+                        merged.queries.push(query.to_string());
+                    }
+                }
+                None => {
+                    self.index.insert(pair.pair_address.clone(), self.pairs.len());
+                    self.pairs.push(MergedPair { pair, queries: vec![query.to_string()] });
+                }
+            }
+        }
+    }
+
+    pub fn pairs(&self) -> &[MergedPair] {
+        &self.pairs
+    }
+
+    /// Unique pairs in the batch.
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    /// Pairs returned across all queries, duplicates included.
+    pub fn fetched(&self) -> usize {
+        self.fetched
+    }
+}
+
+/// The scan → analyze → paper-trade → notify → record pipeline, run once per cycle over the merged query results.
+pub struct Scanner {
+    pub engine: AnalysisEngine,
+    pub http: Arc<HttpClient>,
+    pub dex_client: Arc<DexScreenerClient>,
+    pub rug_client: RugCheckClient,
+    pub notifier: Notifier,
+    pub db: Database,
+    pub paper_engine: Arc<PaperTradingEngine>,
+    pub blacklist: Arc<BlacklistStore>,
+    pub history: Arc<PairHistory>,
+    pub alerts: AlertGate,
+}
+
+impl Scanner {
+    /// Builds the clients and notifier from `config.api` / `config.telegram`, all sharing
+    /// one rate-limited `HttpClient` configured by `config.http`.
+    pub fn new(config: &Config, db: Database, paper_engine: Arc<PaperTradingEngine>) -> Result<Self> {
+        let http = Arc::new(HttpClient::new(&config.http)?);
+        let blacklist = Arc::new(BlacklistStore::in_memory());
+        let history = Arc::new(PairHistory::new(&config.history));
+        let mut engine = AnalysisEngine::new().with_history(Arc::clone(&history));
+        engine.rules_mut().register(rules::Blacklist::new(Arc::clone(&blacklist)));
+        Ok(Self {
+            engine,
+            dex_client: Arc::new(DexScreenerClient::with_http(&config.api.dexscreener_url, Arc::clone(&http))),
+            rug_client: RugCheckClient::with_http(&config.api.rugcheck_url, Arc::clone(&http))
+                .with_cache(ReportCache::new(&config.rugcheck)),
+            notifier: Notifier::new(config, Arc::clone(&http)),
+            http,
+            db,
+            paper_engine,
+            blacklist,
+            history,
+            alerts: AlertGate::new(),
+        })
+    }
+
+    /// Checks `store` (typically `BlacklistStore::from_config`) instead of the default in-memory blacklist.
+    pub fn with_blacklist(mut self, store: Arc<BlacklistStore>) -> Self {
+        self.engine.rules_mut().register(rules::Blacklist::new(Arc::clone(&store)));
+        self.blacklist = store;
+        self
+    }
+
+    /// Fetches `query` and merges its pairs into `batch`. Returns how many pairs the query returned.
+    pub async fn gather(&self, batch: &mut ScanBatch, query: &str) -> Result<usize> {
+        let pairs = self.dex_client.search_pairs(query).await?.pairs;
+        let count = pairs.len();
+        batch.add(query, pairs);
+        Ok(count)
+    }
+
+    /// Runs one query through the pipeline and returns the snapshots it recorded.
+    pub async fn scan_query(&self, query: &str, config: &Config) -> Result<Vec<SnapshotRecord>> {
+        let mut batch = ScanBatch::new();
+        self.gather(&mut batch, query).await?;
+        Ok(self.scan_batch(batch, config).await)
+    }
+
+    /// Security-checks (with the chain's provider), analyzes, alerts on and records every pair in
+    /// `batch` on an allowed chain once, however many queries surfaced it, and returns the snapshots it recorded.
+    pub async fn scan_batch(&self, batch: ScanBatch, config: &Config) -> Vec<SnapshotRecord> {
+        let mut pairs = batch.pairs;
+        let before = pairs.len();
+        pairs.retain(|p| config.chain_allowed(&p.pair.chain_id));
+        if pairs.len() < before {
+            log::info!("Skipped {} pairs on chains outside chains.allow", before - pairs.len());
+        }
+        let mut records = Vec::with_capacity(pairs.len());
+        let now = Utc::now().timestamp();
+        if config.new_launch.enabled {
+            // Youngest first, so fresh launches get their rugcheck before the rate limit bites.
+            pairs.sort_by(|a, b| {
+                let age = |p: &MergedPair| p.pair.age_minutes(now).unwrap_or(f64::MAX);
+                age(a).total_cmp(&age(b))
+            });
+        }
+
+        // Alert-worthy pairs grouped by base mint, in scan order, as indices into `records`.
+        let mut alerted: Vec<(String, Vec<usize>)> = Vec::new();
+        for MergedPair { pair, queries } in pairs {
+            let rug_report = match config.security_provider(&pair.chain_id) {
+                SecurityProvider::Rugcheck => self.rug_client.scan_token(&pair.base_token.address).await.ok(),
+                SecurityProvider::Disabled => None,
+            };
+
+            let report = self.engine.evaluate_at(&pair, config, rug_report.as_ref(), now);
+            self.history.record(&pair, now);
+
+            if report.is_alert(config) {
+                println!(
+                    "✅ SIGNAL: {} found ({}, score {:.0}, age {}).",
+                    pair.base_token.symbol,
+                    signal_title(&report),
+                    report.score,
+                    format_age(pair.age_minutes(now))
+                );
+                let mint = &pair.base_token.address;
+                match alerted.iter_mut().find(|(m, _)| m == mint) {
+                    Some((_, group)) => group.push(records.len()),
+                    None => alerted.push((mint.clone(), vec![records.len()])),
+                }
+            }
+
+            let record = SnapshotRecord::new(now, queries, pair, rug_report, report);
+            if let Err(e) = self.db.save_snapshot(&record).await {
+                log::warn!("Failed to record snapshot: {}", e);
+            }
+            records.push(record);
+        }
+
+        // Each token is entered and alerted on through its best pair only.
+        for (mint, group) in alerted {
+            let analysis = |i: usize| records[i].analysis.as_ref().expect("alerted records carry their analysis");
+            // The best score leads; ties go to the deepest pool.
+            let rank = |i: usize| (analysis(i).score, records[i].pair.liquidity_usd().unwrap_or(0.0));
+            let best = *group
+                .iter()
+                .max_by(|a, b| {
+                    let (a, b) = (rank(**a), rank(**b));
+                    a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1))
+                })
+                .expect("groups are never empty");
+            let (pair, report) = (&records[best].pair, analysis(best));
+            if report.is_entry(config) && config.paper_trading.enabled {
+                self.paper_engine.process_signal(pair, config).await;
+            }
+            let note = match self.alerts.admit(&mint, report, now, &config.alerts) {
+                AlertDecision::Send => None,
+                AlertDecision::Improved { previous } => Some(format!("Score up from {:.0}", previous)),
+                AlertDecision::Suppress { remaining_secs } => {
+                    log::info!("Alert for {} suppressed, cooldown has {}s left", pair.base_token.symbol, remaining_secs);
+                    continue;
+                }
+            };
+            let others: Vec<&Pair> = group.iter().filter(|i| **i != best).map(|i| &records[*i].pair).collect();
+            self.notifier.send(format_signal(pair, report, config, &others, note.as_deref(), now)).await;
+            if let Some(watcher) = self.paper_engine.watcher() {
+                watcher.watch(pair, now, &config.liquidity_watch);
+            }
+        }
+
+        records
+    }
+}
+
+/// The Telegram alert for a signal, MarkdownV2-escaped, listing the score and every reason.
+/// `others` are further qualifying pairs of the same token; `note` explains a re-alert. The pair's
+/// age is taken as of `now` (unix seconds), the time the batch was evaluated.
+pub fn format_signal(pair: &Pair, report: &AnalysisReport, config: &Config, others: &[&Pair], note: Option<&str>, now: i64) -> String {
+    let bonk_link = format!("https://t.me/bonkbot_bot?start={}_{}", config.telegram.bonkbot_ref, pair.base_token.address);
+    let reasons: String = report.reasons().iter().map(|r| format!("\n• {}", escape(r))).collect();
+    let body = escape(&format!(
+        "💰 Mcap: ${:?}\n💧 Liq: ${:?}\n📈 Vol: ${:.2}\n⏱ Age: {}\n🎯 Score: {:.0}/100",
+        pair.market_cap,
+        pair.liquidity_usd(),
+        pair.volume.h24,
+        format_age(pair.age_minutes(now)),
+        report.score
+    ));
+    let note = note.map(|n| format!("\n🔁 {}", escape(n))).unwrap_or_default();
+    let others: String = match others {
+        [] => String::new(),
+        _ => {
+            let lines: String = others
+                .iter()
+                .map(|p| escape(&format!("\n• {} {} (liq ${:.0})", p.dex_id, p.pair_address, p.liquidity_usd().unwrap_or(0.0))))
+                .collect();
+            format!("\n\n{}{}", escape(&format!("Also on {} more pair(s):", others.len())), lines)
+        }
+    };
+    format!(
+        "💎 *{}: {}*\n\n{}{}{}{}\n\n[🚀 OPEN IN BONKBOT]({})",
+        escape(&signal_title(report)),
+        escape(&format!("{} ({})", pair.base_token.name, pair.base_token.symbol)),
+        body,
+        note,
+        reasons,
+        others,
+        escape_link_url(&bonk_link)
+    )
+}
+
+/// The alert headline for the report's pattern.
+fn signal_title(report: &AnalysisReport) -> String {
+    match &report.pattern {
+        MarketPattern::Custom(name) => format!("SIGNAL {}", name),
+        MarketPattern::EarlyAccumulation => "EARLY ACCUMULATION".to_string(),
+        MarketPattern::Distribution => "DISTRIBUTION / EXIT".to_string(),
+        MarketPattern::NewLaunch => "NEW LAUNCH".to_string(),
+        _ => "GOOD SIGNAL".to_string(),
+    }
+}
+
+/// `42m`, `5h 12m` or `3d 4h`; `unknown` without a creation time.
+pub fn format_age(minutes: Option<f64>) -> String {
+    let Some(minutes) = minutes else { return "unknown".to_string() };
+    let minutes = minutes as u64;
+    match minutes {
+        0..=59 => format!("{}m", minutes),
+        60..=1439 => format!("{}h {}m", minutes / 60, minutes % 60),
+        _ => format!("{}d {}h", minutes / 1440, minutes % 1440 / 60),
+    }
+}
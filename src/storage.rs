@@ -1,9 +1,69 @@
 use anyhow::Result;
+use crate::analysis::{AnalysisReport, MarketPattern};
 use crate::models::Pair;
-use chrono::Utc;
-use std::fs::OpenOptions;
-use std::io::Write;
-use serde_json::json;
+use crate::portfolio::PortfolioSnapshot;
+use crate::rugcheck::RugCheckResponse;
+use crate::trading::{ActiveTrade, ClosedTrade};
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+
+/// Bump whenever the shape of `SnapshotRecord` changes in a way readers must know about.
+pub const SNAPSHOT_VERSION: u32 = 2;
+
+/// One line of `dex_data.jsonl`: everything the scanner saw and decided for a pair in one scan.
+///
+/// Version 0 lines (no `v` field) come from the baseline build, which only logged
+/// `ts`/`addr`/`sym`/`pattern` and no pair; they are counted and skipped on load.
+/// Version 1 lines have no `analysis`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SnapshotRecord {
+    #[serde(default)]
+    pub v: u32,
+    pub ts: i64,
+    /// The first query that surfaced the pair this cycle.
+    pub query: Option<String>,
+    /// Every query that surfaced the pair this cycle; absent from lines written before queries were merged.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub queries: Vec<String>,
+    pub pattern: Option<MarketPattern>,
+    pub pair: Pair,
+    pub rugcheck: Option<RugCheckResponse>,
+    #[serde(default)]
+    pub analysis: Option<AnalysisReport>,
+}
+
+impl SnapshotRecord {
+    pub fn new(ts: i64, queries: Vec<String>, pair: Pair, rugcheck: Option<RugCheckResponse>, analysis: AnalysisReport) -> Self {
+        Self {
+            v: SNAPSHOT_VERSION,
+            ts,
+            query: queries.first().cloned(),
+            queries,
+            pattern: Some(analysis.pattern.clone()),
+            pair,
+            rugcheck,
+            analysis: Some(analysis),
+        }
+    }
+}
+
+/// A baseline (version 0) line: `{ts, addr, sym, pattern}`, with nothing to replay.
+fn is_baseline(line: &str) -> bool {
+    serde_json::from_str::<serde_json::Value>(line)
+        .is_ok_and(|v| v.get("ts").is_some() && v.get("addr").is_some() && v.get("pair").is_none())
+}
+
+/// What `Database::load_snapshots` found in the file.
+#[derive(Debug, Default)]
+pub struct LoadedSnapshots {
+    /// Replayable snapshots, in file order.
+    pub records: Vec<SnapshotRecord>,
+    /// Baseline `ts`/`addr`/`sym`/`pattern` lines, which carry no pair.
+    pub baseline: usize,
+    /// Unreadable lines and lines from a newer format.
+    pub skipped: usize,
+}
 
 pub struct Database {
     file_path: String,
@@ -20,27 +80,150 @@ impl Database {
         Ok(Self { file_path: path.to_string() })
     }
 
-    pub async fn save_pair(&self, pair: &Pair) -> Result<()> {
-        let now = Utc::now().timestamp();
-        
-        let record = json!({
-            "timestamp": now,
-            "address": pair.pair_address,
-            "chain_id": pair.chain_id,
-            "base": pair.base_token.symbol,
-            "price_usd": pair.price_usd,
-            "liquidity": pair.liquidity.as_ref().and_then(|l| l.usd),
-            "volume_h24": pair.volume.h24,
-            "buys": pair.txns.h24.buys,
-            "sells": pair.txns.h24.sells,
-        });
+    pub async fn save_snapshot(&self, record: &SnapshotRecord) -> Result<()> {
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(&self.file_path)?;
+
+        writeln!(file, "{}", serde_json::to_string(record)?)?;
+
+        Ok(())
+    }
+
+    /// Loads every replayable snapshot in file order, counting the baseline lines and
+    /// the unreadable or newer-format lines it had to skip.
+    pub async fn load_snapshots(&self) -> Result<LoadedSnapshots> {
+        let reader = BufReader::new(File::open(&self.file_path)?);
+        let mut loaded = LoadedSnapshots::default();
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<SnapshotRecord>(&line) {
+                Ok(record) if record.v <= SNAPSHOT_VERSION => loaded.records.push(record),
+                Ok(_) => loaded.skipped += 1,
+                Err(_) if is_baseline(&line) => loaded.baseline += 1,
+                Err(_) => loaded.skipped += 1,
+            }
+        }
+        Ok(loaded)
+    }
+}
+
+/// One line of the paper-trading journal.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum TradeEvent {
+    Open(ActiveTrade),
+    /// The position after a partial exit, replacing the one opened.
+    Partial(ActiveTrade),
+    /// A new high for an open position, so its trailing stop survives a restart.
+    Mark {
+        address: String,
+        time: i64,
+        peak_price: f64,
+        last_price: Option<f64>,
+    },
+    Close(ClosedTrade),
+}
+
+/// Append-only JSONL log of paper-trade entries and exits. Replaying it yields
+/// the positions still open and the full closed-trade history.
+pub struct TradeJournal {
+    file_path: String,
+}
+
+impl TradeJournal {
+    pub fn new(path: &str) -> Result<Self> {
+        let _ = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
 
+        Ok(Self { file_path: path.to_string() })
+    }
+
+    pub fn path(&self) -> &str {
+        &self.file_path
+    }
+
+    pub fn append(&self, event: &TradeEvent) -> Result<()> {
         let mut file = OpenOptions::new()
             .append(true)
             .open(&self.file_path)?;
 
-        writeln!(file, "{}", record.to_string())?;
+        writeln!(file, "{}", serde_json::to_string(event)?)?;
 
         Ok(())
     }
+
+    /// Replays the journal into `(open, closed)` trades. Unreadable lines are logged and skipped.
+    pub fn load(&self) -> Result<(Vec<ActiveTrade>, Vec<ClosedTrade>)> {
+        let reader = BufReader::new(File::open(&self.file_path)?);
+        let mut open: Vec<ActiveTrade> = Vec::new();
+        let mut closed = Vec::new();
+        for (n, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<TradeEvent>(&line) {
+                Ok(TradeEvent::Open(trade) | TradeEvent::Partial(trade)) => {
+                    open.retain(|t| t.address != trade.address);
+                    open.push(trade);
+                }
+                Ok(TradeEvent::Mark { address, peak_price, last_price, .. }) => {
+                    if let Some(trade) = open.iter_mut().find(|t| t.address == address) {
+                        trade.peak_price = trade.peak_price.max(peak_price);
+                        trade.last_price = last_price.or(trade.last_price);
+                    }
+                }
+                Ok(TradeEvent::Close(trade)) => {
+                    open.retain(|t| t.address != trade.address);
+                    closed.push(trade);
+                }
+                Err(e) => log::warn!("{}:{}: skipping unreadable journal entry: {}", self.file_path, n + 1, e),
+            }
+        }
+        Ok((open, closed))
+    }
+}
+
+/// Append-only JSONL of portfolio snapshots (`portfolio.equity_path`), one per trade-monitor tick.
+pub struct EquityCurve {
+    file_path: String,
+}
+
+impl EquityCurve {
+    pub fn new(path: &str) -> Result<Self> {
+        let _ = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+
+        Ok(Self { file_path: path.to_string() })
+    }
+
+    pub fn append(&self, snapshot: &PortfolioSnapshot) -> Result<()> {
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(&self.file_path)?;
+
+        writeln!(file, "{}", serde_json::to_string(snapshot)?)?;
+
+        Ok(())
+    }
+
+    /// Every snapshot on record, oldest first. Unreadable lines are skipped.
+    pub fn load(&self) -> Result<Vec<PortfolioSnapshot>> {
+        let reader = BufReader::new(File::open(&self.file_path)?);
+        let mut points = Vec::new();
+        for line in reader.lines() {
+            if let Ok(point) = serde_json::from_str(&line?) {
+                points.push(point);
+            }
+        }
+        Ok(points)
+    }
 }
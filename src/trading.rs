@@ -0,0 +1,613 @@
+use crate::client::DexScreenerClient;
+use crate::config::{Config, PaperTradingConfig, TakeProfitStep};
+use crate::execution::{self, Fill};
+use crate::models::Pair;
+use crate::notifier::Notifier;
+use crate::portfolio::{self, PortfolioSnapshot, SolPriceFeed};
+use crate::storage::{TradeEvent, TradeJournal};
+use crate::watcher::{format_pull_alert, LiquidityWatcher};
+use anyhow::Result;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use teloxide::utils::markdown::escape;
+use tokio::sync::Mutex;
+
+/// How a position is exited, fixed from `[paper_trading]` when it is opened so a config reload
+/// doesn't change the plan of trades already open.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ExitStrategy {
+    /// Never empty: a plain `take_profit_percent` is a single step selling everything.
+    pub take_profit: Vec<TakeProfitStep>,
+    pub stop_loss_percent: f64,
+    pub trailing_stop_percent: Option<f64>,
+    pub breakeven_after_first_tp: bool,
+    pub max_hold_secs: Option<u64>,
+}
+
+impl ExitStrategy {
+    pub fn from_config(config: &PaperTradingConfig) -> Self {
+        let take_profit = match config.take_profit_ladder.is_empty() {
+            true => vec![TakeProfitStep { at_percent: config.take_profit_percent, sell_percent: 100.0 }],
+            false => config.take_profit_ladder.clone(),
+        };
+        Self {
+            take_profit,
+            stop_loss_percent: config.stop_loss_percent,
+            trailing_stop_percent: config.trailing_stop_percent,
+            breakeven_after_first_tp: config.breakeven_after_first_tp,
+            max_hold_secs: config.max_hold_secs,
+        }
+    }
+}
+
+/// Part of a position sold before it closed.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct PartialFill {
+    pub time: i64,
+    pub price: f64,
+    /// Share of the original position sold, 0..1.
+    pub fraction: f64,
+    pub tokens: f64,
+    pub fees_sol: f64,
+    pub reason: ExitReason,
+    /// USD per SOL when it filled; `None` for fills journaled before it was recorded.
+    #[serde(default)]
+    pub sol_usd: Option<f64>,
+}
+
+/// What the exit strategy wants done at the current price.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedExit {
+    pub reason: ExitReason,
+    /// Share of the original position to sell, or `None` to close what is left.
+    pub partial: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ActiveTrade {
+    pub address: String,
+    pub symbol: String,
+    pub entry_price: f64,
+    pub amount_sol: f64,
+    pub entry_time: i64,
+    /// Tokens bought; zero for positions journaled before fills were simulated.
+    #[serde(default)]
+    pub tokens: f64,
+    /// Priority fee paid on entry, in SOL.
+    #[serde(default)]
+    pub fees_sol: f64,
+    /// USD per SOL at entry; `None` for positions journaled before it was recorded.
+    #[serde(default)]
+    pub entry_sol_usd: Option<f64>,
+    /// Highest price seen since entry, for the trailing stop.
+    #[serde(default)]
+    pub peak_price: f64,
+    /// Last price seen, for marking the position to market.
+    #[serde(default)]
+    pub last_price: Option<f64>,
+    /// `None` for positions journaled before exit strategies; they follow the current config.
+    #[serde(default)]
+    pub strategy: Option<ExitStrategy>,
+    #[serde(default)]
+    pub partial_fills: Vec<PartialFill>,
+}
+
+impl ActiveTrade {
+    /// Unrealized PnL at `current_price`, before exit costs.
+    pub fn pnl_percent(&self, current_price: f64) -> f64 {
+        ((current_price - self.entry_price) / self.entry_price) * 100.0
+    }
+
+    /// Share of the original position still held.
+    pub fn remaining(&self) -> f64 {
+        (1.0 - self.partial_fills.iter().map(|p| p.fraction).sum::<f64>()).max(0.0)
+    }
+
+    pub fn remaining_tokens(&self) -> f64 {
+        self.tokens * self.remaining()
+    }
+
+    pub fn mark_price(&self) -> f64 {
+        self.last_price.unwrap_or(self.entry_price)
+    }
+
+    /// SOL for `tokens` (`fraction` of the position) at `price` USD while SOL is at `sol_usd`.
+    /// Positions journaled without a token count or SOL price scale the entry by the price change.
+    fn sale_sol(&self, tokens: f64, fraction: f64, price: f64, sol_usd: Option<f64>) -> f64 {
+        match sol_usd {
+            Some(sol_usd) if self.tokens > 0.0 && sol_usd > 0.0 => tokens * price / sol_usd,
+            _ => fraction * self.amount_sol * price / self.entry_price,
+        }
+    }
+
+    /// SOL value of what is still held at `price` USD, with SOL at `sol_usd`.
+    pub fn value_sol(&self, price: f64, sol_usd: f64) -> f64 {
+        self.sale_sol(self.remaining_tokens(), self.remaining(), price, Some(sol_usd))
+    }
+
+    /// SOL received from partial exits, each at the SOL price of its fill, after their priority fees.
+    pub fn proceeds_sol(&self) -> f64 {
+        self.partial_fills.iter().map(|p| self.sale_sol(p.tokens, p.fraction, p.price, p.sol_usd) - p.fees_sol).sum()
+    }
+
+    /// `realized_sol` in USD: each partial exit at the SOL price of its fill, the cost of what they
+    /// sold and the entry fee at the entry's. `sol_usd` stands in for prices never recorded.
+    pub fn realized_usd(&self, sol_usd: f64) -> f64 {
+        let sold: f64 = self.partial_fills.iter().map(|p| p.fraction).sum();
+        let received: f64 = self
+            .partial_fills
+            .iter()
+            .map(|p| (self.sale_sol(p.tokens, p.fraction, p.price, p.sol_usd) - p.fees_sol) * p.sol_usd.unwrap_or(sol_usd))
+            .sum();
+        received - (sold * self.amount_sol + self.fees_sol) * self.entry_sol_usd.unwrap_or(sol_usd)
+    }
+
+    /// Unrealized PnL in USD at `price`: what is held, valued at the current SOL price, against its cost at entry.
+    pub fn unrealized_usd(&self, price: f64, sol_usd: f64) -> f64 {
+        self.value_sol(price, sol_usd) * sol_usd - self.remaining() * self.amount_sol * self.entry_sol_usd.unwrap_or(sol_usd)
+    }
+
+    /// PnL already locked in by partial exits, net of every priority fee paid so far.
+    pub fn realized_sol(&self) -> f64 {
+        let sold: f64 = self.partial_fills.iter().map(|p| p.fraction).sum();
+        self.proceeds_sol() - sold * self.amount_sol - self.fees_sol
+    }
+
+    /// PnL of the whole position if the rest is closed with `exit`: the partial fills and the
+    /// final fill weighted by the share each sold, net of every priority fee.
+    pub fn realized_pnl_percent(&self, exit: &Fill) -> f64 {
+        let partial: f64 = self.partial_fills.iter().map(|p| p.fraction * self.pnl_percent(p.price)).sum();
+        let fees = self.fees_sol + self.partial_fills.iter().map(|p| p.fees_sol).sum::<f64>() + exit.fees_sol;
+        partial + self.remaining() * self.pnl_percent(exit.price) - fees / self.amount_sol * 100.0
+    }
+
+    /// Tracks the peak and decides whether to exit at `price`. Stops and the hold-time limit close
+    /// what is left; take-profit steps sell their share, closing when nothing would remain.
+    pub fn plan_exit(&mut self, price: f64, now: i64, config: &PaperTradingConfig) -> Option<PlannedExit> {
+        self.peak_price = self.peak_price.max(price);
+        self.last_price = Some(price);
+        let strategy = self.strategy.clone().unwrap_or_else(|| ExitStrategy::from_config(config));
+        let close = |reason| Some(PlannedExit { reason, partial: None });
+        let pnl = self.pnl_percent(price);
+        let took_profit = self.partial_fills.iter().any(|p| p.reason == ExitReason::TakeProfit);
+
+        if strategy.max_hold_secs.is_some_and(|max| now - self.entry_time >= max as i64) {
+            return close(ExitReason::MaxHoldTime);
+        }
+        if pnl <= -strategy.stop_loss_percent {
+            return close(ExitReason::StopLoss);
+        }
+        if strategy.breakeven_after_first_tp && took_profit && price <= self.entry_price {
+            return close(ExitReason::Breakeven);
+        }
+        if let Some(trail) = strategy.trailing_stop_percent {
+            if self.peak_price > self.entry_price && price <= self.peak_price * (1.0 - trail / 100.0) {
+                return close(ExitReason::TrailingStop);
+            }
+        }
+
+        let steps_taken = self.partial_fills.iter().filter(|p| p.reason == ExitReason::TakeProfit).count();
+        let step = strategy.take_profit.get(steps_taken).filter(|step| pnl >= step.at_percent)?;
+        let fraction = step.sell_percent / 100.0;
+        match self.remaining() - fraction <= 1e-9 {
+            true => close(ExitReason::TakeProfit),
+            false => Some(PlannedExit { reason: ExitReason::TakeProfit, partial: Some(fraction) }),
+        }
+    }
+
+    /// Tokens `exit` sells.
+    pub fn exit_tokens(&self, exit: &PlannedExit) -> f64 {
+        match exit.partial {
+            Some(fraction) => self.tokens * fraction,
+            None => self.remaining_tokens(),
+        }
+    }
+
+    /// Closes what is left with `exit`, filled while SOL was at `sol_usd`.
+    pub fn close(self, exit: &Fill, sol_usd: f64, now: i64, reason: ExitReason) -> ClosedTrade {
+        let last_sol = self.sale_sol(exit.tokens, self.remaining(), exit.price, Some(sol_usd)) - exit.fees_sol;
+        let net_sol = self.realized_sol() + last_sol - self.remaining() * self.amount_sol;
+        let net_usd = self.realized_usd(sol_usd) + last_sol * sol_usd - self.remaining() * self.amount_sol * self.entry_sol_usd.unwrap_or(sol_usd);
+        ClosedTrade {
+            net_sol: Some(net_sol),
+            net_usd: Some(net_usd),
+            entry_sol_usd: self.entry_sol_usd,
+            exit_sol_usd: Some(sol_usd),
+            pnl_percent: self.realized_pnl_percent(exit),
+            hold_secs: now - self.entry_time,
+            fees_sol: self.fees_sol + self.partial_fills.iter().map(|p| p.fees_sol).sum::<f64>() + exit.fees_sol,
+            address: self.address,
+            symbol: self.symbol,
+            entry_price: self.entry_price,
+            exit_price: exit.price,
+            amount_sol: self.amount_sol,
+            entry_time: self.entry_time,
+            exit_time: now,
+            partial_fills: self.partial_fills,
+            reason,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum ExitReason {
+    TakeProfit,
+    StopLoss,
+    EndOfData,
+    /// Forced by the liquidity watcher.
+    LiquidityPull,
+    /// Fell `trailing_stop_percent` below the peak.
+    TrailingStop,
+    /// Back to the entry price after a take-profit fill.
+    Breakeven,
+    /// Held for `max_hold_secs`.
+    MaxHoldTime,
+}
+
+impl ExitReason {
+    /// Exits that cut risk rather than take profit. They fill whatever the price impact: waiting
+    /// for the pool to recover would only deepen the loss.
+    pub fn ignores_tolerance(&self) -> bool {
+        !matches!(self, ExitReason::TakeProfit)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ClosedTrade {
+    pub address: String,
+    pub symbol: String,
+    pub entry_price: f64,
+    /// Price of the final fill; earlier ones are in `partial_fills`.
+    pub exit_price: f64,
+    pub amount_sol: f64,
+    pub entry_time: i64,
+    pub exit_time: i64,
+    /// Of the whole position, net of priority fees; swap fees and price impact are already in the fill prices.
+    pub pnl_percent: f64,
+    #[serde(default)]
+    pub hold_secs: i64,
+    /// Priority fees paid on entry and every exit, in SOL.
+    #[serde(default)]
+    pub fees_sol: f64,
+    #[serde(default)]
+    pub partial_fills: Vec<PartialFill>,
+    pub reason: ExitReason,
+    /// USD per SOL at entry and at the final fill.
+    #[serde(default)]
+    pub entry_sol_usd: Option<f64>,
+    #[serde(default)]
+    pub exit_sol_usd: Option<f64>,
+    /// SOL and USD gained or lost, every fill counted at its own SOL price. `None` for trades
+    /// closed before SOL prices were recorded.
+    #[serde(default)]
+    pub net_sol: Option<f64>,
+    #[serde(default)]
+    pub net_usd: Option<f64>,
+}
+
+impl ClosedTrade {
+    pub fn pnl_sol(&self) -> f64 {
+        self.net_sol.unwrap_or(self.amount_sol * self.pnl_percent / 100.0)
+    }
+
+    /// `sol_usd` converts trades closed before SOL prices were recorded.
+    pub fn pnl_usd(&self, sol_usd: f64) -> f64 {
+        self.net_usd.unwrap_or_else(|| self.pnl_sol() * sol_usd)
+    }
+}
+
+pub struct PaperTradingEngine {
+    pub active_trades: Arc<Mutex<Vec<ActiveTrade>>>,
+    pub closed_trades: Arc<Mutex<Vec<ClosedTrade>>>,
+    journal: Option<TradeJournal>,
+    watcher: Option<Arc<LiquidityWatcher>>,
+    sol_price: Option<Arc<SolPriceFeed>>,
+}
+
+impl Default for PaperTradingEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PaperTradingEngine {
+    /// An in-memory engine; nothing survives the process (used by backtests).
+    pub fn new() -> Self {
+        Self {
+            active_trades: Arc::new(Mutex::new(Vec::new())),
+            closed_trades: Arc::new(Mutex::new(Vec::new())),
+            journal: None,
+            watcher: None,
+            sol_price: None,
+        }
+    }
+
+    /// An engine that records every entry and exit to `journal` and resumes the
+    /// open positions and trade history found in it.
+    pub fn with_journal(journal: TradeJournal) -> Result<Self> {
+        let (active, closed) = journal.load()?;
+        if !active.is_empty() {
+            println!("♻️ [PAPER TRADE] Restored {} open position(s) from {}", active.len(), journal.path());
+        }
+        Ok(Self {
+            active_trades: Arc::new(Mutex::new(active)),
+            closed_trades: Arc::new(Mutex::new(closed)),
+            journal: Some(journal),
+            watcher: None,
+            sol_price: None,
+        })
+    }
+
+    /// Checks held tokens for liquidity pulls on every `monitor_trades` tick, exiting ahead of TP/SL.
+    pub fn with_watcher(mut self, watcher: Arc<LiquidityWatcher>) -> Self {
+        self.watcher = Some(watcher);
+        self
+    }
+
+    pub fn watcher(&self) -> Option<&Arc<LiquidityWatcher>> {
+        self.watcher.as_ref()
+    }
+
+    /// Sizes buys and values the portfolio with a live SOL price instead of `execution.sol_price_usd`.
+    pub fn with_sol_price(mut self, feed: Arc<SolPriceFeed>) -> Self {
+        self.sol_price = Some(feed);
+        self
+    }
+
+    /// USD per SOL: the feed's last price, else `execution.sol_price_usd`.
+    pub fn sol_usd(&self, config: &Config) -> f64 {
+        self.sol_price.as_ref().map_or(config.execution.sol_price_usd, |feed| feed.price_or_fallback(config))
+    }
+
+    /// USD per SOL for a swap in `pair`: implied by the pool when it is quoted in SOL, else `sol_usd`.
+    pub fn sol_usd_for(&self, pair: &Pair, config: &Config) -> f64 {
+        execution::sol_price_usd(pair, self.sol_usd(config))
+    }
+
+    /// The portfolio as of `now`, with positions marked at their last seen prices.
+    pub async fn portfolio(&self, config: &Config, now: i64) -> PortfolioSnapshot {
+        let active = self.active_trades.lock().await;
+        let closed = self.closed_trades.lock().await;
+        PortfolioSnapshot::compute(config.portfolio.starting_balance_sol, &active, &closed, self.sol_usd(config), now)
+    }
+
+    fn record(&self, event: &TradeEvent) {
+        if let Some(journal) = &self.journal {
+            if let Err(e) = journal.append(event) {
+                log::warn!("Failed to journal paper trade: {}", e);
+            }
+        }
+    }
+
+    /// Journals `trade`'s peak if it rose above `previous_peak` and no exit recorded the position.
+    /// Called at most once per position per monitor tick.
+    fn record_peak(&self, trade: &ActiveTrade, previous_peak: f64, now: i64) {
+        if trade.peak_price > previous_peak {
+            self.record(&TradeEvent::Mark {
+                address: trade.address.clone(),
+                time: now,
+                peak_price: trade.peak_price,
+                last_price: trade.last_price,
+            });
+        }
+    }
+
+    async fn record_close(&self, closed: ClosedTrade) -> ClosedTrade {
+        self.record(&TradeEvent::Close(closed.clone()));
+        self.closed_trades.lock().await.push(closed.clone());
+        closed
+    }
+
+    pub async fn process_signal(&self, pair: &Pair, config: &Config) {
+        self.process_signal_at(pair, config, Utc::now().timestamp()).await;
+    }
+
+    /// Same as `process_signal`, but with an explicit entry timestamp (used by backtests).
+    pub async fn process_signal_at(&self, pair: &Pair, config: &Config, now: i64) {
+        let mut trades = self.active_trades.lock().await;
+        if trades.iter().any(|t| t.address == pair.base_token.address) {
+            return;
+        }
+
+        let amount_sol = config.paper_trading.buy_amount_sol;
+        let cash = portfolio::cash_sol(config.portfolio.starting_balance_sol, &trades, &self.closed_trades.lock().await);
+        let cost = amount_sol + if config.execution.enabled { config.execution.priority_fee_sol } else { 0.0 };
+        if cash < cost {
+            println!("⚠️ [PAPER TRADE] SKIP: {} needs {:.4} SOL, {:.4} SOL available", pair.base_token.symbol, cost, cash);
+            return;
+        }
+        let sol_usd = self.sol_usd_for(pair, config);
+        let Some(fill) = execution::buy(pair, amount_sol, sol_usd, &config.execution) else { return };
+        if fill.exceeds_tolerance(&config.execution) {
+            println!(
+                "⚠️ [PAPER TRADE] SKIP: {} price impact {:.2}% > tolerance {:.2}%",
+                pair.base_token.symbol,
+                fill.impact_percent.unwrap_or_default(),
+                config.execution.slippage_tolerance_percent
+            );
+            return;
+        }
+
+        let trade = ActiveTrade {
+            address: pair.base_token.address.clone(),
+            symbol: pair.base_token.symbol.clone(),
+            entry_price: fill.price,
+            amount_sol,
+            entry_time: now,
+            tokens: fill.tokens,
+            fees_sol: fill.fees_sol,
+            entry_sol_usd: Some(sol_usd),
+            peak_price: fill.price,
+            last_price: Some(fill.price),
+            strategy: Some(ExitStrategy::from_config(&config.paper_trading)),
+            partial_fills: Vec::new(),
+        };
+        self.record(&TradeEvent::Open(trade.clone()));
+        trades.push(trade);
+        println!("📈 [PAPER TRADE] ENTER: {} at ${:.8}{}", pair.base_token.symbol, fill.price, describe_impact(&fill));
+    }
+
+    pub async fn monitor_trades(&self, client: &DexScreenerClient, config: &Config, notifier: &Notifier) {
+        let mut trades = self.active_trades.lock().await;
+        let now = Utc::now().timestamp();
+
+        let mut idx = 0;
+        while idx < trades.len() {
+            let trade = &mut trades[idx];
+            idx += 1;
+            let Ok(data) = client.get_token_pairs(&trade.address).await else { continue };
+            let pair = match &self.watcher {
+                Some(watcher) => watcher.pick(&trade.address, &data.pairs),
+                None => data.pairs.first(),
+            };
+            // Observed before the price is needed: a drained pool may no longer quote one, or be delisted.
+            let pulled = self.watcher.as_ref().and_then(|w| w.observe(&trade.address, pair, now, &config.liquidity_watch));
+            let current_price = pair.and_then(Pair::price);
+            if let Some(price) = current_price {
+                println!("📊 [PAPER TRADE] {} PnL: {:.2}%", trade.symbol, trade.pnl_percent(price));
+            }
+
+            let peak = trade.peak_price;
+            let exit = match pulled {
+                Some(_) => PlannedExit { reason: ExitReason::LiquidityPull, partial: None },
+                None => match current_price.and_then(|price| trade.plan_exit(price, now, &config.paper_trading)) {
+                    Some(exit) => exit,
+                    None => {
+                        self.record_peak(trade, peak, now);
+                        continue;
+                    }
+                },
+            };
+            let tokens = trade.exit_tokens(&exit);
+            let fill = match pair.and_then(|pair| execution::sell(pair, tokens, &config.execution)) {
+                Some(fill) => fill,
+                // The watcher has already reset its readings, so the pull must be booked now:
+                // with no usable price left, assume the tokens are worthless.
+                None if pulled.is_some() => Fill { price: 0.0, tokens, impact_percent: None, fees_sol: 0.0 },
+                None => {
+                    self.record_peak(trade, peak, now);
+                    continue;
+                }
+            };
+
+            if let Some(drop) = pulled {
+                // A forced exit takes whatever fill the drained pool gives.
+                let realized = trade.realized_pnl_percent(&fill);
+                println!("🚨 [PAPER TRADE] LIQUIDITY PULL: {} {}, exiting at {:.2}% PnL", trade.symbol, drop.describe(), realized);
+                notifier.send(format_pull_alert(&trade.symbol, &drop, Some((fill.price, realized)))).await;
+            } else if fill.exceeds_tolerance(&config.execution) && !exit.reason.ignores_tolerance() {
+                println!(
+                    "⚠️ [PAPER TRADE] EXIT DEFERRED: {} price impact {:.2}% > tolerance {:.2}%",
+                    trade.symbol,
+                    fill.impact_percent.unwrap_or_default(),
+                    config.execution.slippage_tolerance_percent
+                );
+                self.record_peak(trade, peak, now);
+                continue;
+            } else {
+                match exit.partial {
+                    Some(fraction) => println!(
+                        "💰 [PAPER TRADE] PARTIAL EXIT: {} sold {:.0}% at {:.2}% PnL{}",
+                        trade.symbol,
+                        fraction * 100.0,
+                        trade.pnl_percent(fill.price),
+                        describe_impact(&fill)
+                    ),
+                    None => println!(
+                        "📉 [PAPER TRADE] EXIT: {} at {:.2}% PnL ({:?}){}",
+                        trade.symbol,
+                        trade.realized_pnl_percent(&fill),
+                        exit.reason,
+                        describe_impact(&fill)
+                    ),
+                }
+                notifier.send(format_exit(trade, &exit, &fill)).await;
+            }
+            let sol_usd = pair.map_or(self.sol_usd(config), |pair| self.sol_usd_for(pair, config));
+            if self.execute(&mut trades, idx - 1, &exit, &fill, sol_usd, now).await.is_some() {
+                idx -= 1;
+            }
+        }
+    }
+
+    /// Applies `exit` to the position at `idx`, filled while SOL was at `sol_usd`: records a partial
+    /// fill, or closes the position and returns the closed trade.
+    async fn execute(&self, trades: &mut Vec<ActiveTrade>, idx: usize, exit: &PlannedExit, fill: &Fill, sol_usd: f64, now: i64) -> Option<ClosedTrade> {
+        let Some(fraction) = exit.partial else {
+            let trade = trades.remove(idx);
+            return Some(self.record_close(trade.close(fill, sol_usd, now, exit.reason.clone())).await);
+        };
+        let trade = &mut trades[idx];
+        trade.partial_fills.push(PartialFill {
+            time: now,
+            price: fill.price,
+            fraction,
+            tokens: fill.tokens,
+            fees_sol: fill.fees_sol,
+            reason: exit.reason.clone(),
+            sol_usd: Some(sol_usd),
+        });
+        self.record(&TradeEvent::Partial(trade.clone()));
+        None
+    }
+
+    /// Runs the exit strategy of the position on `address` against `pair` without notifying,
+    /// as `monitor_trades` does (used by backtests). Returns the trade if it closed.
+    pub async fn manage_position(&self, address: &str, pair: &Pair, now: i64, config: &Config) -> Option<ClosedTrade> {
+        let mut trades = self.active_trades.lock().await;
+        let idx = trades.iter().position(|t| t.address == address)?;
+        let peak = trades[idx].peak_price;
+        let planned = trades[idx].plan_exit(pair.price()?, now, &config.paper_trading);
+        let fill = planned.as_ref().and_then(|exit| execution::sell(pair, trades[idx].exit_tokens(exit), &config.execution));
+        let (Some(exit), Some(fill)) = (planned, fill) else {
+            self.record_peak(&trades[idx], peak, now);
+            return None;
+        };
+        if fill.exceeds_tolerance(&config.execution) && !exit.reason.ignores_tolerance() {
+            self.record_peak(&trades[idx], peak, now);
+            return None;
+        }
+        self.execute(&mut trades, idx, &exit, &fill, self.sol_usd_for(pair, config), now).await
+    }
+
+    /// Closes the open position on `address` with `fill`, filled while SOL was at `sol_usd`, returning the realized trade.
+    pub async fn close_trade_at(&self, address: &str, fill: &Fill, sol_usd: f64, now: i64, reason: ExitReason) -> Option<ClosedTrade> {
+        let mut trades = self.active_trades.lock().await;
+        let idx = trades.iter().position(|t| t.address == address)?;
+        let trade = trades.remove(idx);
+        Some(self.record_close(trade.close(fill, sol_usd, now, reason)).await)
+    }
+}
+
+/// The Telegram message for a take-profit step or a closed position.
+fn format_exit(trade: &ActiveTrade, exit: &PlannedExit, fill: &Fill) -> String {
+    match exit.partial {
+        Some(fraction) => format!(
+            "🔔 *PAPER TRADE PARTIAL EXIT*\n\n{}",
+            escape(&format!(
+                "Token: {}\nSold: {:.0}% at {:+.2}%\nExit Price: ${:.8}\nStill held: {:.0}%",
+                trade.symbol,
+                fraction * 100.0,
+                trade.pnl_percent(fill.price),
+                fill.price,
+                (trade.remaining() - fraction) * 100.0
+            ))
+        ),
+        None => format!(
+            "🔔 *PAPER TRADE CLOSED*\n\n{}",
+            escape(&format!(
+                "Token: {}\nResult: {:.2}%\nExit Price: ${:.8}\nReason: {:?}",
+                trade.symbol,
+                trade.realized_pnl_percent(fill),
+                fill.price,
+                exit.reason
+            ))
+        ),
+    }
+}
+
+/// ` (impact 0.42%)`, or nothing when the fill had no reserves to model.
+fn describe_impact(fill: &Fill) -> String {
+    fill.impact_percent.map(|i| format!(" (impact {:.2}%)", i)).unwrap_or_default()
+}
@@ -0,0 +1,180 @@
+use crate::client::DexScreenerClient;
+use crate::config::{Config, LiquidityWatchConfig};
+use crate::models::Pair;
+use crate::notifier::Notifier;
+use dashmap::DashMap;
+use std::collections::{HashSet, VecDeque};
+use teloxide::utils::markdown::escape;
+
+/// Pool reserves at one observation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LiquidityReading {
+    pub ts: i64,
+    pub usd: Option<f64>,
+    pub base: f64,
+    pub quote: f64,
+}
+
+/// What a `LiquidityDrop` was measured on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DropMetric {
+    Usd,
+    /// Both base and quote reserves fell; `from` / `to` are then quote reserves.
+    Reserves,
+}
+
+/// A liquidity drop past `liquidity_watch.drop_percent` within the window.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LiquidityDrop {
+    pub metric: DropMetric,
+    pub from: f64,
+    pub to: f64,
+    pub percent: f64,
+    /// Seconds between the peak reading and now.
+    pub within_secs: i64,
+}
+
+struct Watched {
+    pair_address: Option<String>,
+    /// Last symbol seen, for alerts once the pair itself is gone.
+    symbol: Option<String>,
+    /// End of the alert watch; `None` while only watched because the token is held.
+    until: Option<i64>,
+    readings: VecDeque<LiquidityReading>,
+}
+
+/// Watches the liquidity of tokens we hold or have alerted on, across successive readings,
+/// so a pulled pool triggers an urgent alert (and, for held tokens, a forced paper exit).
+#[derive(Default)]
+pub struct LiquidityWatcher {
+    watched: DashMap<String, Watched>,
+}
+
+impl LiquidityWatcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts (or extends) watching the base token of `pair` for `watch_alerted_secs`.
+    pub fn watch(&self, pair: &Pair, now: i64, config: &LiquidityWatchConfig) {
+        let until = now + config.watch_alerted_secs as i64;
+        let mut entry = self.watched.entry(pair.base_token.address.clone()).or_insert_with(|| Watched {
+            pair_address: None,
+            symbol: None,
+            until: None,
+            readings: VecDeque::new(),
+        });
+        entry.pair_address = Some(pair.pair_address.clone());
+        entry.symbol = Some(pair.base_token.symbol.clone());
+        entry.until = Some(entry.until.map_or(until, |u| u.max(until)));
+    }
+
+    pub fn is_watching(&self, mint: &str) -> bool {
+        self.watched.contains_key(mint)
+    }
+
+    /// The pair to read for `mint`: the one we alerted on if still listed, else the first.
+    pub fn pick<'a>(&self, mint: &str, pairs: &'a [Pair]) -> Option<&'a Pair> {
+        let wanted = self.watched.get(mint).and_then(|w| w.pair_address.clone());
+        wanted
+            .and_then(|addr| pairs.iter().find(|p| p.pair_address == addr))
+            .or_else(|| pairs.first())
+    }
+
+    /// The symbol last seen for `mint`, or the mint itself if it was never seen with a pair.
+    pub fn symbol(&self, mint: &str) -> String {
+        self.watched.get(mint).and_then(|w| w.symbol.clone()).unwrap_or_else(|| mint.to_string())
+    }
+
+    /// Records the current reserves of `mint` and reports a drop past the threshold relative to
+    /// the peak within `window_secs`. A reported drop resets the readings so it fires once.
+    /// A pair that is no longer listed (`None`) or reports no liquidity reads as an empty pool.
+    pub fn observe(&self, mint: &str, pair: Option<&Pair>, now: i64, config: &LiquidityWatchConfig) -> Option<LiquidityDrop> {
+        if !config.enabled {
+            return None;
+        }
+        let mut entry = self.watched.entry(mint.to_string()).or_insert_with(|| Watched {
+            pair_address: pair.map(|p| p.pair_address.clone()),
+            symbol: None,
+            until: None,
+            readings: VecDeque::new(),
+        });
+        if let Some(pair) = pair {
+            entry.symbol = Some(pair.base_token.symbol.clone());
+        }
+        let window = config.window_secs as i64;
+        entry.readings.retain(|r| now - r.ts <= window);
+        let current = match pair.and_then(|p| p.liquidity.as_ref()) {
+            Some(liquidity) => LiquidityReading { ts: now, usd: liquidity.usd, base: liquidity.base, quote: liquidity.quote },
+            None => LiquidityReading { ts: now, usd: Some(0.0), base: 0.0, quote: 0.0 },
+        };
+
+        let drop = |now_value: f64, peak: Option<(f64, i64)>| {
+            let (peak, at) = peak?;
+            let percent = (peak - now_value) / peak * 100.0;
+            (peak > 0.0 && percent >= config.drop_percent).then_some((peak, percent, at))
+        };
+        let peak_of = |get: fn(&LiquidityReading) -> Option<f64>| {
+            entry.readings.iter().filter_map(|r| Some((get(r)?, r.ts))).max_by(|a, b| a.0.total_cmp(&b.0))
+        };
+
+        let usd = current.usd.and_then(|usd| drop(usd, peak_of(|r| r.usd)).map(|d| (usd, d)));
+        let reserves = drop(current.base, peak_of(|r| Some(r.base))).zip(drop(current.quote, peak_of(|r| Some(r.quote))));
+        let found = match (usd, reserves) {
+            (Some((usd, (peak, percent, at))), _) => Some(LiquidityDrop { metric: DropMetric::Usd, from: peak, to: usd, percent, within_secs: now - at }),
+            // Both sides of the pool shrinking at once is a withdrawal, not trading.
+            (None, Some((_, (peak, percent, at)))) => {
+                Some(LiquidityDrop { metric: DropMetric::Reserves, from: peak, to: current.quote, percent, within_secs: now - at })
+            }
+            _ => None,
+        };
+
+        if found.is_some() {
+            entry.readings.clear();
+        } else {
+            entry.readings.push_back(current);
+        }
+        found
+    }
+
+    /// Reads every watched token not in `held` (those are read by the trade monitor) and alerts on
+    /// pulls. Watches past their expiry are dropped.
+    pub async fn check_alerted(&self, client: &DexScreenerClient, notifier: &Notifier, config: &Config, held: &HashSet<String>, now: i64) {
+        self.watched.retain(|mint, w| held.contains(mint) || w.until.is_some_and(|until| until > now));
+        let mints: Vec<String> = self.watched.iter().map(|e| e.key().clone()).filter(|m| !held.contains(m)).collect();
+
+        for mint in mints {
+            let Ok(data) = client.get_token_pairs(&mint).await else { continue };
+            let pair = self.pick(&mint, &data.pairs);
+            if let Some(drop) = self.observe(&mint, pair, now, &config.liquidity_watch) {
+                let symbol = self.symbol(&mint);
+                println!("🚨 LIQUIDITY PULL: {} {}", symbol, drop.describe());
+                notifier.send(format_pull_alert(&symbol, &drop, None)).await;
+            }
+        }
+    }
+}
+
+impl LiquidityDrop {
+    /// e.g. `usd liquidity $52000 -> $9000 (-82.7%) in 60s`.
+    pub fn describe(&self) -> String {
+        match self.metric {
+            DropMetric::Usd => format!("usd liquidity ${:.0} -> ${:.0} (-{:.1}%) in {}s", self.from, self.to, self.percent, self.within_secs),
+            DropMetric::Reserves => format!("base and quote reserves down {:.1}% in {}s", self.percent, self.within_secs),
+        }
+    }
+}
+
+/// The urgent MarkdownV2 alert for a pull; `exit` is the forced paper exit's (price, pnl %), if any.
+pub fn format_pull_alert(symbol: &str, drop: &LiquidityDrop, exit: Option<(f64, f64)>) -> String {
+    let exit_line = match exit {
+        Some((price, pnl)) => format!("\nForced paper exit at ${:.8} ({:.2}%)", price, pnl),
+        None => String::new(),
+    };
+    format!(
+        "🚨 *LIQUIDITY PULLED: {}*\n\n{}{}",
+        escape(symbol),
+        escape(&drop.describe()),
+        escape(&exit_line)
+    )
+}
@@ -0,0 +1,216 @@
+mod common;
+
+use common::{fixture_pair, good_pair, good_report, pair, scratch_dir, NOW};
+use dex_bot::alerts::{AlertDecision, AlertGate};
+use dex_bot::analysis::{AnalysisEngine, AnalysisReport, FindingKind, MarketPattern};
+use dex_bot::config::{ChainProfile, Config, SecurityProvider};
+use dex_bot::execution::WSOL_MINT;
+use dex_bot::history::PairHistory;
+use dex_bot::models::Pair;
+use dex_bot::rugcheck::RugCheckResponse;
+use dex_bot::scanner::{format_signal, ScanBatch};
+use std::sync::Arc;
+
+fn evaluate(pair: &Pair, config: &Config, report: Option<&RugCheckResponse>) -> AnalysisReport {
+    AnalysisEngine::new().evaluate_at(pair, config, report, NOW)
+}
+
+fn has_reason(report: &AnalysisReport, text: &str) -> bool {
+    report.reasons().iter().any(|r| r.contains(text))
+}
+
+#[test]
+fn momentum_frames_classify_accumulation_and_distribution() {
+    let config = Config::default();
+    let mut accum = fixture_pair(0);
+    accum["volume"]["m5"] = 5000.0.into();
+    accum["priceChange"] = serde_json::json!({"m5": 2.0, "h1": 8.0, "h6": 12.0, "h24": 20.0});
+    let mut dump = fixture_pair(0);
+    dump["txns"]["m5"] = serde_json::json!({"buys": 10, "sells": 40});
+    dump["txns"]["h1"] = serde_json::json!({"buys": 100, "sells": 300});
+    dump["priceChange"] = serde_json::json!({"m5": -3.0, "h1": -10.0, "h6": 40.0, "h24": 120.0});
+
+    let accum = evaluate(&pair(accum), &config, Some(&good_report()));
+    let dump = evaluate(&pair(dump), &config, Some(&good_report()));
+
+    assert_eq!(accum.pattern, MarketPattern::EarlyAccumulation);
+    assert_eq!(dump.pattern, MarketPattern::Distribution);
+    // Both are alerted on but neither is traded.
+    assert!(accum.is_alert(&config) && !accum.is_entry(&config));
+    assert!(dump.is_alert(&config) && !dump.is_entry(&config));
+    assert!(format_signal(&good_pair(), &accum, &config, &[], None, NOW).contains("EARLY ACCUMULATION"));
+    assert!(format_signal(&good_pair(), &dump, &config, &[], None, NOW).contains("DISTRIBUTION"));
+}
+
+#[test]
+fn new_launches_get_stricter_checks_and_old_pairs_age_out() {
+    let mut config = Config::default();
+    config.new_launch.enabled = true;
+    config.rugcheck.only_good = false;
+    config.filters.max_pair_age_minutes = Some(24.0 * 60.0);
+    let ten_minutes_ago = (NOW - 600) * 1000;
+    let mut fresh = fixture_pair(0);
+    fresh["pairCreatedAt"] = ten_minutes_ago.into();
+    fresh["priceChange"]["m5"] = 2.0.into();
+    let mut risky = fixture_pair(2);
+    risky["pairCreatedAt"] = ten_minutes_ago.into();
+    let risk_report: RugCheckResponse =
+        serde_json::from_str(&common::fixture("v1/tokens/RiskMint11111111111111111111111111111111111/report")).unwrap();
+
+    assert_eq!(evaluate(&pair(fresh), &config, Some(&good_report())).pattern, MarketPattern::NewLaunch);
+    let risky = evaluate(&pair(risky), &config, Some(&risk_report));
+    assert_eq!(risky.pattern, MarketPattern::RugcheckRisk);
+    assert!(has_reason(&risky, "new launch (10m old) risk score 18500"), "{:?}", risky.reasons());
+
+    // Chains without a security provider can't produce a report, so none is required there.
+    config.chains.allow = vec!["solana".to_string(), "base".to_string()];
+    let mut base = fixture_pair(0);
+    base["chainId"] = "base".into();
+    base["pairCreatedAt"] = ten_minutes_ago.into();
+    base["priceChange"]["m5"] = 2.0.into();
+    let base = evaluate(&pair(base), &config, None);
+    assert_eq!(base.pattern, MarketPattern::NewLaunch, "{:?}", base.reasons());
+    let mut unchecked = fixture_pair(0);
+    unchecked["pairCreatedAt"] = ten_minutes_ago.into();
+    let unchecked = evaluate(&pair(unchecked), &config, None);
+    assert!(has_reason(&unchecked, "new launch (10m old) without a rugcheck report"), "{:?}", unchecked.reasons());
+
+    // A day and a minute old: past the max age.
+    let old = AnalysisEngine::new().evaluate_at(&good_pair(), &config, Some(&good_report()), NOW + 24 * 3600 + 60);
+    assert_eq!(old.pattern, MarketPattern::Unknown);
+}
+
+#[test]
+fn history_deltas_flag_liquidity_pulls_and_survive_a_reload() {
+    let mut config = Config::default();
+    config.history.path = Some(scratch_dir("history").join("history.json").to_string_lossy().into_owned());
+    let history = Arc::new(PairHistory::new(&config.history));
+    let engine = AnalysisEngine::new().with_history(Arc::clone(&history));
+
+    let first = engine.evaluate_at(&good_pair(), &config, Some(&good_report()), NOW);
+    assert_eq!(first.pattern, MarketPattern::GoodCandidate);
+    history.record(&good_pair(), NOW);
+
+    let mut pulled = fixture_pair(0);
+    pulled["liquidity"]["usd"] = 15000.0.into();
+    let pulled = pair(pulled);
+    let second = engine.evaluate_at(&pulled, &config, Some(&good_report()), NOW + 60);
+    assert_eq!(second.pattern, MarketPattern::LiquidityPull);
+    assert!(second.reasons()[0].contains("liquidity -70.0%"), "{:?}", second.reasons());
+    history.record(&pulled, NOW + 60);
+
+    history.save(NOW + 60).unwrap();
+    let samples = PairHistory::new(&config.history).samples(&pulled.pair_address);
+    assert_eq!(samples.len(), 2);
+    assert_eq!(samples[1].liquidity_usd, Some(15000.0));
+}
+
+#[test]
+fn alert_gate_cools_down_per_mint_and_lets_improvements_through() {
+    let mut config = Config::default();
+    config.alerts.cooldown_secs = 600;
+    config.alerts.realert_score_delta = 10.0;
+    let gate = AlertGate::new();
+    let report = |score: f64, pattern: MarketPattern| AnalysisReport { pattern, score, findings: Vec::new() };
+    let good = |score| report(score, MarketPattern::GoodCandidate);
+
+    assert_eq!(gate.admit("Mint", &good(60.0), NOW, &config.alerts), AlertDecision::Send);
+    assert_eq!(gate.admit("Other", &good(60.0), NOW, &config.alerts), AlertDecision::Send);
+    assert_eq!(gate.admit("Mint", &good(65.0), NOW + 100, &config.alerts), AlertDecision::Suppress { remaining_secs: 500 });
+    assert_eq!(gate.admit("Mint", &good(70.0), NOW + 200, &config.alerts), AlertDecision::Improved { previous: 60.0 });
+    // A different pattern is news regardless of score.
+    assert_eq!(gate.admit("Mint", &report(40.0, MarketPattern::Distribution), NOW + 300, &config.alerts), AlertDecision::Send);
+    assert_eq!(gate.last("Mint").unwrap().pattern, MarketPattern::Distribution);
+    // The cooldown runs from the last alert sent.
+    assert_eq!(gate.admit("Mint", &report(40.0, MarketPattern::Distribution), NOW + 900, &config.alerts), AlertDecision::Send);
+}
+
+#[test]
+fn scan_batch_merges_pairs_by_address_keeping_the_latest_fetch() {
+    let mut fresher = fixture_pair(0);
+    fresher["volume"]["h24"] = 123456.0.into();
+    let mut batch = ScanBatch::new();
+    batch.add("pump", vec![good_pair(), pair(fixture_pair(1))]);
+    batch.add("solana", vec![pair(fresher)]);
+    batch.add("solana", vec![pair(fixture_pair(2))]);
+
+    assert_eq!((batch.len(), batch.fetched()), (3, 4));
+    let merged = &batch.pairs()[0];
+    assert_eq!(merged.queries, ["pump", "solana"]);
+    assert_eq!(merged.pair.volume.h24, 123456.0);
+    assert_eq!(batch.pairs()[1].queries, ["pump"]);
+    assert_eq!(batch.pairs()[2].queries, ["solana"]);
+}
+
+#[test]
+fn chain_profiles_override_filters_and_pick_the_security_provider() {
+    let mut config = Config::default();
+    config.chains.allow = vec!["solana".to_string(), "base".to_string()];
+    config.chains.profiles.insert("solana".to_string(), ChainProfile { quote_tokens: vec![WSOL_MINT.to_string()], ..ChainProfile::default() });
+    config.chains.profiles.insert("base".to_string(), ChainProfile { min_liquidity_usd: Some(100_000.0), ..ChainProfile::default() });
+    config.validate().unwrap();
+
+    assert!(config.chain_allowed("base") && !config.chain_allowed("bsc"));
+    assert_eq!(config.security_provider("solana"), SecurityProvider::Rugcheck);
+    assert_eq!(config.security_provider("base"), SecurityProvider::Disabled);
+    assert_eq!(config.filters_for("base").min_liquidity_usd, 100_000.0);
+    assert_eq!(config.filters_for("solana").min_liquidity_usd, config.filters.min_liquidity_usd);
+
+    let mut usdc = fixture_pair(0);
+    usdc["quoteToken"]["symbol"] = "USDC".into();
+    usdc["quoteToken"]["address"] = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".into();
+    let usdc = evaluate(&pair(usdc), &config, Some(&good_report()));
+    assert!(has_reason(&usdc, "quote token USDC is not whitelisted on solana"), "{:?}", usdc.reasons());
+    // Calling a token "SOL" doesn't make it wrapped SOL.
+    let mut fake_sol = fixture_pair(0);
+    fake_sol["quoteToken"]["address"] = "FakeSo1111111111111111111111111111111111112".into();
+    let fake_sol = evaluate(&pair(fake_sol), &config, Some(&good_report()));
+    assert!(has_reason(&fake_sol, "quote token SOL is not whitelisted on solana"), "{:?}", fake_sol.reasons());
+
+    let mut base = fixture_pair(0);
+    base["chainId"] = "base".into();
+    let base = evaluate(&pair(base), &config, None);
+    assert!(has_reason(&base, "< min $100000"), "{:?}", base.reasons());
+
+    config.chains.profiles.get_mut("base").unwrap().security = Some(SecurityProvider::Rugcheck);
+    let err = config.validate().unwrap_err().to_string();
+    assert!(err.contains("chains.profiles.base.security: rugcheck does not support chain \"base\""), "{}", err);
+}
+
+#[test]
+fn dex_and_quote_lists_filter_and_quotes_are_normalized() {
+    let mut config = Config::default();
+    config.markets.dex_deny = vec!["sketchyswap".to_string()];
+    config.markets.quote_deny = vec!["ScamQuote1111111111111111111111111111111111".to_string()];
+    config.validate().unwrap();
+    let with = |edit: &dyn Fn(&mut serde_json::Value)| {
+        let mut value = fixture_pair(0);
+        edit(&mut value);
+        evaluate(&pair(value), &config, Some(&good_report()))
+    };
+
+    let good = with(&|_| {});
+    assert_eq!(good.pattern, MarketPattern::GoodCandidate);
+    assert!(!has_reason(&good, "quote_normalization"));
+    assert!(has_reason(&with(&|p| p["dexId"] = "sketchyswap".into()), "dex sketchyswap is denied"));
+    let scam = with(&|p| p["quoteToken"] = serde_json::json!({"address": "ScamQuote1111111111111111111111111111111111", "name": "Scam", "symbol": "SCAM"}));
+    assert!(has_reason(&scam, "quote token SCAM is denied"));
+    // Symbols don't count: a look-alike "USDC" is weighed as any other token.
+    let fake_usdc = with(&|p| p["quoteToken"] = serde_json::json!({"address": "FakeUSDC111111111111111111111111111111111111", "name": "USD Coin", "symbol": "USDC"}));
+    assert!(has_reason(&fake_usdc, "quote USDC (other, x0.50)"), "{:?}", fake_usdc.findings);
+
+    let mut symbols = Config::default();
+    symbols.markets.stable_quotes = vec!["USDC".to_string()];
+    let err = symbols.validate().unwrap_err().to_string();
+    assert!(err.contains("markets.stable_quotes must list mint addresses, not symbols (got \"USDC\")"), "{}", err);
+
+    let meme = with(&|p| {
+        p["quoteToken"] = serde_json::json!({"address": "MemeQuote", "name": "Meme", "symbol": "MEME"});
+        p["liquidity"]["usd"] = 1500.0.into();
+        p["volume"]["h24"] = 12000.0.into();
+    });
+    assert_eq!(meme.pattern, MarketPattern::Unknown);
+    let note = meme.findings.iter().find(|f| f.kind == FindingKind::Note).unwrap();
+    assert!(note.detail.contains("quote MEME (other, x0.50): liquidity $1500 -> $750"), "{}", note.detail);
+    assert!(has_reason(&meme, "normalized liquidity $750 < min $1000"));
+}
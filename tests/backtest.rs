@@ -0,0 +1,84 @@
+mod common;
+
+use common::{fixture_pair, good_pair, good_report, pair, scratch_dir, GOOD_MINT, NOW};
+use dex_bot::analysis::AnalysisEngine;
+use dex_bot::backtest;
+use dex_bot::config::Config;
+use dex_bot::models::Pair;
+use dex_bot::storage::{Database, SnapshotRecord, SNAPSHOT_VERSION};
+use dex_bot::trading::ExitReason;
+use serde_json::json;
+use std::io::Write;
+
+/// The good fixture pair quoted at `price` USD with SOL held at $150.
+fn priced(price: f64) -> Pair {
+    let mut value = fixture_pair(0);
+    value["priceUsd"] = price.to_string().into();
+    value["priceNative"] = (price / 150.0).to_string().into();
+    pair(value)
+}
+
+/// A current-version snapshot of `pair` at `ts`, analyzed the way the scanner would.
+fn snapshot(pair: Pair, ts: i64, config: &Config) -> SnapshotRecord {
+    let analysis = AnalysisEngine::new().evaluate_at(&pair, config, Some(&good_report()), ts);
+    SnapshotRecord::new(ts, vec!["pump".to_string()], pair, Some(good_report()), analysis)
+}
+
+#[tokio::test]
+async fn snapshots_of_every_version_load_and_baseline_lines_are_counted() {
+    let path = scratch_dir("snapshot-versions").join("dex_data.jsonl");
+    let current = snapshot(good_pair(), NOW + 120, &Config::default());
+    let lines = [
+        json!({"ts": NOW, "addr": GOOD_MINT, "sym": "GOOD", "pattern": "GoodCandidate"}).to_string(),
+        json!({"v": 1, "ts": NOW + 60, "query": "pump", "pattern": "GoodCandidate", "pair": fixture_pair(0), "rugcheck": null}).to_string(),
+        serde_json::to_string(&current).unwrap(),
+        json!({"v": SNAPSHOT_VERSION + 1, "ts": NOW + 180, "pair": fixture_pair(0)}).to_string(),
+        "not json".to_string(),
+    ];
+    let mut file = std::fs::File::create(&path).unwrap();
+    for line in &lines {
+        writeln!(file, "{}", line).unwrap();
+    }
+
+    let loaded = Database::new(path.to_str().unwrap()).await.unwrap().load_snapshots().await.unwrap();
+    assert_eq!((loaded.records.len(), loaded.baseline, loaded.skipped), (2, 1, 2));
+
+    let v1 = &loaded.records[0];
+    assert_eq!((v1.v, v1.ts, v1.query.as_deref()), (1, NOW + 60, Some("pump")));
+    assert!(v1.queries.is_empty() && v1.analysis.is_none() && v1.rugcheck.is_none());
+    assert_eq!(v1.pair.base_token.address, GOOD_MINT);
+
+    let v2 = &loaded.records[1];
+    assert_eq!((v2.v, v2.queries.as_slice()), (SNAPSHOT_VERSION, ["pump".to_string()].as_slice()));
+    assert!(v2.analysis.is_some() && v2.rugcheck.is_some());
+}
+
+#[tokio::test]
+async fn backtest_replays_a_recorded_file_into_a_trade_report() {
+    let path = scratch_dir("backtest-run").join("dex_data.jsonl");
+    let mut config = Config::default();
+    config.execution.enabled = false;
+    let db = Database::new(path.to_str().unwrap()).await.unwrap();
+    // Recorded out of order: the replay must still run the clock forward.
+    for (secs, price) in [(120, 0.002), (0, 0.001), (60, 0.0011)] {
+        db.save_snapshot(&snapshot(priced(price), NOW + secs, &config)).await.unwrap();
+    }
+    let mut file = std::fs::OpenOptions::new().append(true).open(&path).unwrap();
+    writeln!(file, "{}", json!({"ts": NOW, "addr": GOOD_MINT, "sym": "GOOD", "pattern": "GoodCandidate"})).unwrap();
+
+    let report = backtest::run(path.to_str().unwrap(), &config).await.unwrap();
+    assert_eq!((report.replayed, report.baseline, report.skipped), (3, 1, 0));
+    // The take-profit at +120s re-enters on the same snapshot, and that position is closed at the end.
+    assert_eq!(report.trades.len(), 2);
+    let trade = &report.trades[0];
+    assert_eq!(trade.address, GOOD_MINT);
+    assert_eq!(trade.reason, ExitReason::TakeProfit);
+    assert_eq!((trade.entry_time, trade.exit_time), (NOW, NOW + 120));
+    assert!((trade.pnl_percent - 100.0).abs() < 1e-6, "{}", trade.pnl_percent);
+    assert!(trade.pnl_sol() > 0.0);
+    let reentry = &report.trades[1];
+    assert_eq!(reentry.reason, ExitReason::EndOfData);
+    assert_eq!((reentry.entry_time, reentry.exit_time, reentry.pnl_percent), (NOW + 120, NOW + 120, 0.0));
+
+    assert!(backtest::run(path.with_extension("missing").to_str().unwrap(), &config).await.is_err());
+}
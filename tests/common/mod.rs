@@ -0,0 +1,102 @@
+//! Fixtures and a mock-server pipeline shared by the integration tests.
+#![allow(dead_code)]
+
+use dex_bot::config::Config;
+use dex_bot::mock::MockServer;
+use dex_bot::models::Pair;
+use dex_bot::rugcheck::RugCheckResponse;
+use dex_bot::scanner::Scanner;
+use dex_bot::storage::{Database, SnapshotRecord, TradeJournal};
+use dex_bot::trading::PaperTradingEngine;
+use serde_json::Value;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+pub const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures");
+pub const GOOD_MINT: &str = "GoodMint11111111111111111111111111111111111";
+/// The creation time of the fixture pairs, used as the clock by tests that don't go through the scanner.
+pub const NOW: i64 = 1_760_000_000;
+
+/// The fixture file for `key`, e.g. `latest/dex/tokens/<mint>`.
+pub fn fixture(key: &str) -> String {
+    std::fs::read_to_string(format!("{}/{}.json", FIXTURES, key)).unwrap()
+}
+
+/// Pair `index` of the `pump` search fixture: 0 is GOOD, 1 is WASH (fake volume), 2 is RISK (bad rugcheck).
+pub fn fixture_pair(index: usize) -> Value {
+    serde_json::from_str::<Value>(&fixture("latest/dex/search/pump")).unwrap()["pairs"][index].clone()
+}
+
+pub fn pair(value: Value) -> Pair {
+    serde_json::from_value(value).unwrap()
+}
+
+pub fn good_pair() -> Pair {
+    pair(fixture_pair(0))
+}
+
+pub fn good_report() -> RugCheckResponse {
+    serde_json::from_str(&fixture(&format!("v1/tokens/{}/report", GOOD_MINT))).unwrap()
+}
+
+pub fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("dexbot-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// A scanner wired to a fixture-serving mock of every API, journaling into its own scratch dir.
+pub struct Pipeline {
+    pub server: MockServer,
+    pub config: Config,
+    pub dir: PathBuf,
+    pub scanner: Scanner,
+}
+
+/// Starts a pipeline named `name` after applying `configure` to a config pointed at the mock.
+pub async fn pipeline(name: &str, configure: impl FnOnce(&mut Config)) -> Pipeline {
+    let server = MockServer::start(FIXTURES, "127.0.0.1:0").await.unwrap();
+    let dir = scratch_dir(name);
+    let mut config = Config {
+        queries: vec!["pump".to_string()],
+        ..Config::default()
+    };
+    config.telegram.bot_token = "123:TEST".to_string();
+    config.telegram.chat_id = "12345".to_string();
+    config.paper_trading.journal_path = dir.join("trades.jsonl").to_string_lossy().into_owned();
+    config.api.dexscreener_url = server.url();
+    config.api.rugcheck_url = format!("{}/v1", server.url());
+    config.api.telegram_url = Some(server.url());
+    configure(&mut config);
+    config.validate().unwrap();
+
+    let db = Database::new(dir.join("dex_data.jsonl").to_str().unwrap()).await.unwrap();
+    let journal = TradeJournal::new(&config.paper_trading.journal_path).unwrap();
+    let engine = Arc::new(PaperTradingEngine::with_journal(journal).unwrap());
+    let scanner = Scanner::new(&config, db, engine).unwrap();
+    Pipeline { server, config, dir, scanner }
+}
+
+impl Pipeline {
+    /// Rebuilds the scanner around `engine`, e.g. one with a liquidity watcher or a SOL price feed.
+    pub async fn with_engine(mut self, engine: Arc<PaperTradingEngine>) -> Self {
+        let db = Database::new(self.dir.join("dex_data.jsonl").to_str().unwrap()).await.unwrap();
+        self.scanner = Scanner::new(&self.config, db, engine).unwrap();
+        self
+    }
+
+    /// Answers the search for `query` with `pairs`.
+    pub fn serve(&self, query: &str, pairs: Vec<Value>) {
+        self.server.set_response(&format!("latest/dex/search/{}", query), serde_json::json!({ "pairs": pairs }).to_string());
+    }
+
+    pub async fn scan(&self, query: &str) -> Vec<SnapshotRecord> {
+        self.scanner.scan_query(query, &self.config).await.unwrap()
+    }
+
+    /// Bodies of the Telegram messages sent so far.
+    pub fn alerts(&self) -> Vec<String> {
+        self.server.requests().into_iter().filter(|r| r.path.ends_with("/SendMessage")).map(|r| r.body).collect()
+    }
+}
@@ -0,0 +1,176 @@
+use dex_bot::config::{diff, Config, ConfigHandle};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn env(vars: &[(&str, &str)]) -> Vec<(String, String)> {
+    vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+}
+
+fn load_with(toml: &str, vars: &[(&str, &str)]) -> anyhow::Result<Config> {
+    Config::from_toml_str_with_env(toml, env(vars))
+}
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("dexbot-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn missing_keys_keep_their_defaults() {
+    let config = load_with("queries = [\"moon\"]\n[filters]\nmax_vlr = 20.0\n", &[]).unwrap();
+    assert_eq!(config.queries, ["moon"]);
+    assert_eq!(config.filters.max_vlr, 20.0);
+    assert_eq!(config.filters.min_liquidity_usd, Config::default().filters.min_liquidity_usd);
+}
+
+#[test]
+fn unknown_keys_are_rejected_at_any_depth() {
+    let err = load_with("querys = [\"moon\"]\n", &[]).unwrap_err();
+    assert!(format!("{:#}", err).contains("unknown field `querys`"), "{:#}", err);
+    let err = load_with("[filters]\nmin_liquidty_usd = 1.0\n", &[]).unwrap_err();
+    assert!(format!("{:#}", err).contains("unknown field `min_liquidty_usd`"), "{:#}", err);
+    let err = load_with("", &[("DEXBOT_FILTERS__NOPE", "1")]).unwrap_err();
+    assert!(format!("{:#}", err).contains("unknown field `nope`"), "{:#}", err);
+}
+
+#[test]
+fn env_overrides_parse_toml_literals_by_section_path() {
+    let config = load_with(
+        "[filters]\nmin_liquidity_usd = 1000.0\n",
+        &[
+            ("DEXBOT_FILTERS__MIN_LIQUIDITY_USD", "2000.0"),
+            ("DEXBOT_QUERIES", "[\"pump\", \"moon\"]"),
+            ("DEXBOT_PAPER_TRADING__ENABLED", "false"),
+            ("DEXBOT_API__DEXSCREENER_URL", "http://127.0.0.1:8787"),
+            ("DEXBOT_TELEGRAM__CHAT_ID", "12345"),
+            ("TELEGRAM_BOT_TOKEN", "123:ABC"),
+            ("PATH", "/usr/bin"),
+        ],
+    )
+    .unwrap();
+
+    assert_eq!(config.filters.min_liquidity_usd, 2000.0);
+    assert_eq!(config.queries, ["pump", "moon"]);
+    assert!(!config.paper_trading.enabled);
+    // Not a TOML literal, so taken as a string.
+    assert_eq!(config.api.dexscreener_url, "http://127.0.0.1:8787");
+    // A number, but chat ids are always strings.
+    assert_eq!(config.telegram.chat_id, "12345");
+    assert_eq!(config.telegram.bot_token, "123:ABC");
+}
+
+#[test]
+fn env_overrides_cannot_descend_into_values() {
+    let err = load_with("queries = [\"pump\"]\n", &[("DEXBOT_QUERIES__FIRST", "\"moon\"")]).unwrap_err();
+    assert!(err.to_string().contains("DEXBOT_QUERIES__FIRST overrides `queries`, which is not a table"), "{}", err);
+}
+
+#[test]
+fn validation_reports_every_problem_at_once() {
+    let err = load_with(
+        "[filters]\nmin_liquidity_usd = -1.0\nmax_vlr = 0.0\n[paper_trading]\nbuy_amount_sol = 0.0\n[portfolio]\nstarting_balance_sol = 0.0\n",
+        &[],
+    )
+    .unwrap_err()
+    .to_string();
+
+    let problems: Vec<&str> = err.lines().skip(1).collect();
+    assert_eq!(problems.len(), 4, "{}", err);
+    assert!(problems[0].contains("filters.min_liquidity_usd must be >= 0 (got -1)"), "{}", err);
+    assert!(problems[1].contains("filters.max_vlr must be > 0 (got 0)"), "{}", err);
+    assert!(err.contains("paper_trading.buy_amount_sol must be > 0 (got 0)"), "{}", err);
+    assert!(err.contains("portfolio.starting_balance_sol must be > 0 (got 0)"), "{}", err);
+}
+
+#[test]
+fn load_names_the_file_it_failed_on() {
+    let dir = scratch_dir("config-load");
+    let missing = dir.join("missing.toml");
+    let err = Config::load(&missing).unwrap_err();
+    assert!(err.to_string().contains(&format!("failed to read config file {}", missing.display())), "{:#}", err);
+
+    let path = dir.join("bad.toml");
+    std::fs::write(&path, "[filters]\nmax_vlr = \"high\"\n").unwrap();
+    let err = Config::load(&path).unwrap_err();
+    assert!(err.to_string().contains(&format!("invalid config file {}", path.display())), "{:#}", err);
+
+    std::fs::write(&path, "[filters]\nmax_vlr = 30.0\n").unwrap();
+    assert_eq!(Config::load(&path).unwrap().filters.max_vlr, 30.0);
+}
+
+#[test]
+fn the_example_config_loads() {
+    let config = Config::load(concat!(env!("CARGO_MANIFEST_DIR"), "/config.example.toml")).unwrap();
+    assert!(!config.queries.is_empty());
+}
+
+#[test]
+fn reload_swaps_in_valid_changes_and_reports_them() {
+    let path = scratch_dir("config-reload").join("config.toml");
+    std::fs::write(&path, "[filters]\nmax_vlr = 30.0\n").unwrap();
+    let handle = ConfigHandle::new(Config::load(&path).unwrap(), Some(path.clone()));
+
+    std::fs::write(&path, "[filters]\nmax_vlr = 40.0\n[paper_trading]\ntake_profit_percent = 80.0\n").unwrap();
+    let changes = handle.reload().unwrap();
+    assert_eq!(changes, ["filters.max_vlr: 30.0 -> 40.0", "paper_trading.take_profit_percent: 50.0 -> 80.0"]);
+    assert_eq!(handle.current().filters.max_vlr, 40.0);
+    assert!(handle.reload().unwrap().is_empty());
+}
+
+#[test]
+fn a_rejected_reload_keeps_the_running_config() {
+    let path = scratch_dir("config-reject").join("config.toml");
+    std::fs::write(&path, "[filters]\nmax_vlr = 30.0\n").unwrap();
+    let handle = ConfigHandle::new(Config::load(&path).unwrap(), Some(path.clone()));
+    let before = handle.current();
+
+    std::fs::write(&path, "[filters]\nmax_vlr = -1.0\n").unwrap();
+    assert!(handle.reload().unwrap_err().to_string().contains("invalid config file"));
+    std::fs::write(&path, "[filters\n").unwrap();
+    assert!(handle.reload().is_err());
+
+    assert!(Arc::ptr_eq(&before, &handle.current()));
+}
+
+#[test]
+fn diffs_mask_secrets() {
+    let old = Config::default();
+    let mut new = old.clone();
+    new.telegram.bot_token = "123:SECRET".to_string();
+    new.rugcheck.api_key = Some("also-secret".to_string());
+    new.telegram.chat_id = "42".to_string();
+
+    assert_eq!(
+        diff(&old, &new),
+        [
+            "rugcheck.api_key: <unset> -> ***",
+            "telegram.bot_token: *** -> ***",
+            "telegram.chat_id: \"YOUR_CHAT_ID\" -> \"42\"",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn the_watcher_reloads_when_the_file_changes() {
+    let path = scratch_dir("config-watch").join("config.toml");
+    std::fs::write(&path, "[filters]\nmax_vlr = 30.0\n").unwrap();
+    let handle = Arc::new(ConfigHandle::new(Config::load(&path).unwrap(), Some(path.clone())));
+    let watcher = handle.spawn_watcher(Duration::from_millis(20));
+
+    // Step the mtime forward so filesystems with coarse timestamps still see a change.
+    std::fs::write(&path, "[filters]\nmax_vlr = 45.0\n").unwrap();
+    let later = std::time::SystemTime::now() + Duration::from_secs(5);
+    std::fs::File::options().write(true).open(&path).unwrap().set_modified(later).unwrap();
+
+    for _ in 0..100 {
+        if handle.current().filters.max_vlr == 45.0 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    watcher.abort();
+    assert_eq!(handle.current().filters.max_vlr, 45.0);
+}
@@ -0,0 +1,110 @@
+mod common;
+
+use common::{fixture_pair, good_report, pair, NOW};
+use dex_bot::expr::{EvalContext, Expr};
+use dex_bot::models::Pair;
+use dex_bot::rugcheck::RugCheckResponse;
+
+/// Whether `source` matches the GOOD fixture pair (liquidity $50k, h1 change 18.4%, 310/140 h1 txns).
+fn matches_with(source: &str, pair: &Pair, report: Option<&RugCheckResponse>) -> bool {
+    let ctx = EvalContext { pair, rug_report: report, now: NOW, deltas: None };
+    Expr::compile(source).unwrap_or_else(|e| panic!("{}", e)).matches(&ctx)
+}
+
+fn matches(source: &str) -> bool {
+    matches_with(source, &common::good_pair(), Some(&good_report()))
+}
+
+/// The compile error for `source` and the 1-based column it points at.
+fn error(source: &str) -> (String, usize) {
+    let err = Expr::compile(source).unwrap_err();
+    (err.message.clone(), source[..err.pos].chars().count() + 1)
+}
+
+#[test]
+fn arithmetic_and_logic_follow_precedence() {
+    assert!(matches("1 + 2 * 3 == 7"));
+    assert!(matches("(1 + 2) * 3 == 9"));
+    assert!(matches("10 - 4 - 3 == 3 && 12 / 3 / 2 == 2"));
+    assert!(matches("-2 * -3 == 6"));
+    // `&&` binds tighter than `||`.
+    assert!(matches("true || false && false"));
+    assert!(!matches("(true || false) && false"));
+    assert!(matches("!false && true"));
+    assert!(matches("txns.h1.buys / txns.h1.sells > 2 && liquidity.usd >= 50_000"));
+    assert!(matches("dex_id == \"raydium\" && quote_token.symbol != \"USDC\""));
+}
+
+#[test]
+fn ranges_are_inclusive() {
+    assert!(matches("price_change.h1 in 10..80"));
+    assert!(matches("liquidity.usd in 50000..50000"));
+    assert!(matches("price_change.h1 in 18.4..20"));
+    assert!(!matches("price_change.h1 in 18.5..80"));
+    assert!(matches("price_change.m5 * 2 in 20 + 5..30"));
+    assert!(!matches("price_change.h1 in 80..10"));
+}
+
+#[test]
+fn missing_values_never_match_even_when_negated() {
+    let mut value = fixture_pair(0);
+    value["liquidity"] = serde_json::Value::Null;
+    let no_liquidity = pair(value);
+
+    for source in [
+        "liquidity.usd > 0",
+        "!(liquidity.usd > 0)",
+        "!(liquidity.usd < 0)",
+        "liquidity.usd in 0..1_000_000_000",
+        "!(liquidity.usd in 0..1_000_000_000)",
+        "!!(liquidity.usd > 0)",
+        "true && liquidity.usd > 0",
+        "false || !(liquidity.usd > 0)",
+    ] {
+        assert!(!matches_with(source, &no_liquidity, None), "{}", source);
+    }
+    assert!(!matches("!(volume.h1 / 0 > 1)"));
+    assert!(!matches_with("!(rugcheck.score > 500)", &common::good_pair(), None));
+    assert!(matches_with("!(rugcheck.score > 500)", &common::good_pair(), Some(&good_report())));
+    // A known side still settles `&&` / `||`.
+    assert!(matches_with("!(false && liquidity.usd > 0)", &no_liquidity, None));
+    assert!(matches_with("liquidity.usd > 0 || volume.h24 > 0", &no_liquidity, None));
+}
+
+#[test]
+fn unknown_fields_suggest_related_paths() {
+    let (message, col) = error("liquidity.usd > 1 && txns.h1.buyz > 5");
+    assert_eq!(col, 22);
+    assert!(message.starts_with("unknown field `txns.h1.buyz`; did you mean one of: txns.m5.buys"), "{}", message);
+
+    let (message, col) = error("slippage > 1");
+    assert_eq!(col, 1);
+    assert!(message.contains("available: chain_id, dex_id"), "{}", message);
+}
+
+#[test]
+fn errors_point_at_the_offending_column() {
+    let cases = [
+        ("liquidity.usd > \"big\"", "cannot compare number with string using `>`", 15),
+        ("liquidity.usd && true", "left side of `&&` must be a bool, found number", 1),
+        ("true && 1 + 2", "right side of `&&` must be a bool, found number", 9),
+        ("dex_id in 1..2", "left side of `in` must be a number, found string", 1),
+        ("price_usd in 1", "expected `..` in range", 15),
+        ("!liquidity.usd", "operand of `!` must be a bool, found number", 2),
+        ("(true", "expected `)`", 6),
+        ("true true", "unexpected `true`", 6),
+        ("liquidity.usd > 1 &&", "unexpected end of expression", 21),
+        ("dex_id == 'raydium'", "unexpected character `'`", 11),
+        ("dex_id == \"raydium", "unterminated string", 11),
+        ("liquidity.usd", "expression must be a condition (bool), but it is a number", 1),
+        ("true < false", "`<` needs numbers or strings, found bool", 6),
+    ];
+    for (source, message, col) in cases {
+        let (got, got_col) = error(source);
+        assert!(got.contains(message), "{}: {}", source, got);
+        assert_eq!(got_col, col, "{}: {}", source, got);
+    }
+
+    let err = Expr::compile("fdv >= \"x\"").unwrap_err().to_string();
+    assert_eq!(err, "cannot compare number with string using `>=` (column 5)\n    fdv >= \"x\"\n        ^");
+}
@@ -0,0 +1,115 @@
+mod common;
+
+use chrono::{TimeZone, Utc};
+use common::{fixture_pair, FIXTURES};
+use dex_bot::client::DexScreenerClient;
+use dex_bot::config::HttpConfig;
+use dex_bot::http::{host_of, parse_retry_after, HostStatsSnapshot, HttpClient};
+use dex_bot::mock::MockServer;
+use std::time::{Duration, Instant};
+
+const KEY: &str = "latest/dex/tokens/GoodMint11111111111111111111111111111111111";
+
+/// Fast retries so the tests don't sleep through real backoff.
+fn http_config() -> HttpConfig {
+    HttpConfig { base_backoff_ms: 10, max_backoff_ms: 50, ..HttpConfig::default() }
+}
+
+async fn get(http: &HttpClient, server: &MockServer) -> anyhow::Result<serde_json::Value> {
+    http.get_json(&format!("{}/{}", server.url(), KEY)).await
+}
+
+fn stats(http: &HttpClient, server: &MockServer) -> HostStatsSnapshot {
+    let host = host_of(&server.url());
+    http.stats().into_iter().find(|(h, _)| *h == host).unwrap().1
+}
+
+#[tokio::test]
+async fn search_queries_are_url_encoded() {
+    let server = MockServer::start(FIXTURES, "127.0.0.1:0").await.unwrap();
+    server.set_response("latest/dex/search/sol & usdc #1 ü", serde_json::json!({ "pairs": [fixture_pair(0)] }).to_string());
+    let client = DexScreenerClient::with_base_url(&server.url()).unwrap();
+
+    let pairs = client.search_pairs("sol & usdc #1 ü").await.unwrap().pairs;
+
+    assert_eq!(pairs.len(), 1);
+    assert_eq!(server.requests()[0].query.as_deref(), Some("q=sol+%26+usdc+%231+%C3%BC"));
+}
+
+#[tokio::test]
+async fn server_errors_are_retried_until_they_clear() {
+    let server = MockServer::start(FIXTURES, "127.0.0.1:0").await.unwrap();
+    server.fail_next(KEY, 503, None, 2);
+    let http = HttpClient::new(&http_config()).unwrap();
+
+    assert!(get(&http, &server).await.unwrap()["pairs"].is_array());
+    assert_eq!(server.requests().len(), 3);
+    assert_eq!(stats(&http, &server), HostStatsSnapshot { requests: 3, throttled: 0, retries: 2, failed: 0 });
+}
+
+#[tokio::test]
+async fn retries_give_up_after_max_retries_and_client_errors_are_not_retried() {
+    let server = MockServer::start(FIXTURES, "127.0.0.1:0").await.unwrap();
+    server.fail_next(KEY, 429, None, 10);
+    let http = HttpClient::new(&HttpConfig { max_retries: 2, ..http_config() }).unwrap();
+
+    let err = get(&http, &server).await.unwrap_err();
+    assert!(format!("{:#}", err).contains("giving up after 3 attempts: HTTP 429 Too Many Requests"), "{:#}", err);
+    assert_eq!(stats(&http, &server), HostStatsSnapshot { requests: 3, throttled: 0, retries: 2, failed: 1 });
+
+    let missing = format!("{}/latest/dex/tokens/NoSuchMint", server.url());
+    assert!(http.get_json::<serde_json::Value>(&missing).await.is_err());
+    assert_eq!(stats(&http, &server), HostStatsSnapshot { requests: 4, throttled: 0, retries: 2, failed: 2 });
+}
+
+#[tokio::test]
+async fn retry_after_overrides_the_backoff() {
+    let server = MockServer::start(FIXTURES, "127.0.0.1:0").await.unwrap();
+    server.fail_next(KEY, 429, Some("1"), 1);
+    let http = HttpClient::new(&HttpConfig { max_backoff_ms: 5_000, ..http_config() }).unwrap();
+
+    let started = Instant::now();
+    get(&http, &server).await.unwrap();
+    assert!(started.elapsed() >= Duration::from_secs(1), "{:?}", started.elapsed());
+    assert_eq!(stats(&http, &server).retries, 1);
+}
+
+#[test]
+fn retry_after_accepts_seconds_and_http_dates() {
+    let now = Utc.with_ymd_and_hms(2015, 10, 21, 7, 28, 0).unwrap();
+    assert_eq!(parse_retry_after(" 120 ", now), Some(Duration::from_secs(120)));
+    assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:28:30 GMT", now), Some(Duration::from_secs(30)));
+    // Already passed: retry right away.
+    assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:27:00 GMT", now), Some(Duration::ZERO));
+    assert_eq!(parse_retry_after("soon", now), None);
+    assert_eq!(parse_retry_after("-5", now), None);
+}
+
+#[test]
+fn backoff_doubles_with_jitter_and_caps_retry_after() {
+    let http = HttpClient::new(&HttpConfig { base_backoff_ms: 100, max_backoff_ms: 1_000, ..HttpConfig::default() }).unwrap();
+    for (attempt, full) in [(0, 100), (1, 200), (2, 400), (3, 800), (4, 1_000), (20, 1_000)] {
+        for _ in 0..20 {
+            let delay = http.backoff(attempt, None);
+            assert!(delay >= Duration::from_millis(full / 2) && delay <= Duration::from_millis(full), "attempt {}: {:?}", attempt, delay);
+        }
+    }
+    assert_eq!(http.backoff(0, Some(Duration::from_secs(3))), Duration::from_secs(1));
+    assert_eq!(http.backoff(5, Some(Duration::from_millis(250))), Duration::from_millis(250));
+}
+
+#[tokio::test]
+async fn requests_over_the_host_rate_limit_wait_for_a_token() {
+    let server = MockServer::start(FIXTURES, "127.0.0.1:0").await.unwrap();
+    let mut config = http_config();
+    config.rate_limits.insert(host_of(&server.url()), 2.0);
+    let http = HttpClient::new(&config).unwrap();
+
+    let started = Instant::now();
+    for _ in 0..3 {
+        get(&http, &server).await.unwrap();
+    }
+    // Two tokens in the bucket, then the third waits ~0.5s for a refill.
+    assert!(started.elapsed() >= Duration::from_millis(400), "{:?}", started.elapsed());
+    assert_eq!(stats(&http, &server), HostStatsSnapshot { requests: 3, throttled: 1, retries: 0, failed: 0 });
+}
@@ -0,0 +1,79 @@
+use dex_bot::analysis::{AnalysisEngine, MarketPattern};
+use dex_bot::config::Config;
+use dex_bot::models::{DexScreenerResponse, Pair};
+use dex_bot::rugcheck::RugCheckResponse;
+use serde_json::{json, Value};
+
+/// A DexScreener search result with every timeframe filled in, passing the default filters.
+fn pair_json() -> Value {
+    json!({
+        "chainId": "solana",
+        "dexId": "raydium",
+        "url": "https://dexscreener.com/solana/goodpair",
+        "pairAddress": "GoodPair",
+        "baseToken": {"address": "GoodMint", "name": "Good Token", "symbol": "GOOD"},
+        "quoteToken": {"address": "So11111111111111111111111111111111111111112", "name": "Wrapped SOL", "symbol": "SOL"},
+        "priceNative": "0.0000066",
+        "priceUsd": "0.001",
+        "txns": {
+            "m5": {"buys": 12, "sells": 4},
+            "h1": {"buys": 120, "sells": 60},
+            "h6": {"buys": 600, "sells": 400},
+            "h24": {"buys": 1500, "sells": 1100}
+        },
+        "volume": {"m5": 1500.0, "h1": 9000.0, "h6": 30000.0, "h24": 80000.0},
+        "priceChange": {"m5": 10.0, "h1": 15.0, "h6": 20.0, "h24": 30.0},
+        "liquidity": {"usd": 50000.0, "base": 25000000.0, "quote": 165.0},
+        "fdv": 100000.0,
+        "marketCap": 100000.0,
+        "pairCreatedAt": 1760000000000i64
+    })
+}
+
+fn pair(value: Value) -> Pair {
+    serde_json::from_value(value).unwrap()
+}
+
+fn report(status: &str, bundle_ratio: Option<f64>) -> RugCheckResponse {
+    serde_json::from_value(json!({
+        "score": 100,
+        "status": status,
+        "risks": [{"name": "Mutable metadata", "value": "", "description": "Metadata can be changed", "score": 100, "level": "warn"}],
+        "file_meta": {"bundle_ratio": bundle_ratio}
+    }))
+    .unwrap()
+}
+
+#[test]
+fn search_results_keep_every_timeframe_and_tolerate_missing_ones() {
+    let response: DexScreenerResponse = serde_json::from_value(json!({ "pairs": [pair_json()] })).unwrap();
+    let full = &response.pairs[0];
+    assert_eq!((full.txns.m5.buys, full.txns.h1.sells, full.txns.h6.buys), (12, 60, 600));
+    assert_eq!((full.volume.m5, full.volume.h6), (1500.0, 30000.0));
+    assert_eq!(full.price_change.h1, Some(15.0));
+    assert_eq!((full.price(), full.liquidity_usd()), (Some(0.001), Some(50000.0)));
+
+    // Older snapshots and thin pairs only carry the 24h frame.
+    let mut thin = pair_json();
+    thin["txns"] = json!({"h24": {"buys": 3, "sells": 1}});
+    thin["volume"] = json!({"h24": 80000.0});
+    thin["priceChange"] = json!({"h24": 30.0});
+    let thin = pair(thin);
+    assert_eq!((thin.txns.m5.buys, thin.volume.h1, thin.price_change.m5), (0, 0.0, None));
+
+    assert!(serde_json::from_value::<DexScreenerResponse>(json!({})).unwrap().pairs.is_empty());
+}
+
+#[test]
+fn the_analysis_engine_classifies_library_types() {
+    let config = Config::default();
+    let good = pair(pair_json());
+    assert_eq!(AnalysisEngine::analyze_pair(&good, &config, Some(&report("good", None))), MarketPattern::GoodCandidate);
+
+    let mut wash = pair_json();
+    wash["volume"]["h24"] = 5_000_000.0.into();
+    assert_eq!(AnalysisEngine::analyze_pair(&pair(wash), &config, None), MarketPattern::FakeVolume);
+
+    assert_eq!(AnalysisEngine::analyze_pair(&good, &config, Some(&report("bad", None))), MarketPattern::RugcheckRisk);
+    assert_eq!(AnalysisEngine::analyze_pair(&good, &config, Some(&report("good", Some(0.4)))), MarketPattern::BundledSupply);
+}
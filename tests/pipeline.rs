@@ -0,0 +1,416 @@
+mod common;
+
+use common::{fixture, fixture_pair, pipeline, Pipeline, GOOD_MINT};
+use dex_bot::analysis::{Finding, MarketPattern};
+use dex_bot::blacklist::{BlacklistKind, BlacklistStore};
+use dex_bot::config::{ChainProfile, Config, RuleSettings};
+use dex_bot::rules::{Rule, RuleContext};
+use dex_bot::scanner::ScanBatch;
+use dex_bot::storage::TradeJournal;
+use dex_bot::trading::{ExitReason, PaperTradingEngine};
+use dex_bot::watcher::LiquidityWatcher;
+use std::sync::Arc;
+
+#[tokio::test]
+async fn scan_classifies_enters_notifies_and_records() {
+    let p = pipeline("scan", |_| {}).await;
+
+    let records = p.scan("pump").await;
+
+    let patterns: Vec<(&str, MarketPattern)> = records
+        .iter()
+        .map(|r| (r.pair.base_token.symbol.as_str(), r.pattern.clone().unwrap()))
+        .collect();
+    assert_eq!(
+        patterns,
+        vec![
+            ("GOOD", MarketPattern::GoodCandidate),
+            ("WASH", MarketPattern::FakeVolume),
+            ("RISK", MarketPattern::RugcheckRisk),
+        ]
+    );
+
+    let trades = p.scanner.paper_engine.active_trades.lock().await.clone();
+    assert_eq!(trades.len(), 1);
+    assert_eq!(trades[0].address, GOOD_MINT);
+
+    let alerts = p.alerts();
+    assert_eq!(alerts.len(), 1);
+    assert!(alerts[0].contains("GOOD SIGNAL"));
+    assert!(alerts[0].contains("Score") && alerts[0].contains("momentum"));
+
+    let wash = records[1].analysis.as_ref().unwrap();
+    assert_eq!(wash.findings[0].rule, "fake_volume");
+    assert_eq!(wash.findings[0].threshold, Some(p.config.filters.max_vlr));
+    assert!(wash.score < records[0].analysis.as_ref().unwrap().score);
+
+    let loaded = p.scanner.db.load_snapshots().await.unwrap();
+    assert_eq!((loaded.records.len(), loaded.baseline, loaded.skipped), (3, 0, 0));
+    assert!(loaded.records.iter().all(|s| s.rugcheck.is_some()));
+}
+
+#[tokio::test]
+async fn monitor_takes_profit_and_journals_the_exit() {
+    let p = pipeline("monitor", |c| c.execution.enabled = false).await;
+    p.scan("pump").await;
+
+    let key = format!("latest/dex/tokens/{}", GOOD_MINT);
+    p.server.set_response(&key, fixture(&key).replace("\"priceUsd\": \"0.001\"", "\"priceUsd\": \"0.002\""));
+
+    p.scanner.paper_engine.monitor_trades(&p.scanner.dex_client, &p.config, &p.scanner.notifier).await;
+
+    assert!(p.scanner.paper_engine.active_trades.lock().await.is_empty());
+    let closed = p.scanner.paper_engine.closed_trades.lock().await.clone();
+    assert_eq!(closed.len(), 1);
+    assert_eq!(closed[0].reason, ExitReason::TakeProfit);
+    assert!((closed[0].pnl_percent - 100.0).abs() < 1e-9);
+
+    let closes = p.server.requests().into_iter().filter(|r| r.body.contains("PAPER TRADE CLOSED")).count();
+    assert_eq!(closes, 1);
+
+    // A restart sees the closed trade and no open positions.
+    let (open, history) = TradeJournal::new(&p.config.paper_trading.journal_path).unwrap().load().unwrap();
+    assert!(open.is_empty());
+    assert_eq!(history.len(), 1);
+}
+
+#[tokio::test]
+async fn monitor_stops_out_of_a_thin_pool_past_the_slippage_tolerance() {
+    let p = pipeline("monitor-thin", |_| {}).await;
+    p.scan("pump").await;
+
+    let key = format!("latest/dex/tokens/{}", GOOD_MINT);
+    let mut thin: serde_json::Value = serde_json::from_str(&fixture(&key)).unwrap();
+    thin["pairs"][0]["priceUsd"] = "0.0005".into();
+    thin["pairs"][0]["liquidity"] = serde_json::json!({"usd": 100.0, "base": 1000.0, "quote": 0.5});
+    p.server.set_response(&key, thin.to_string());
+
+    p.scanner.paper_engine.monitor_trades(&p.scanner.dex_client, &p.config, &p.scanner.notifier).await;
+
+    assert!(p.scanner.paper_engine.active_trades.lock().await.is_empty());
+    assert_eq!(p.scanner.paper_engine.closed_trades.lock().await[0].reason, ExitReason::StopLoss);
+    assert!(p.alerts().iter().any(|a| a.contains("PAPER TRADE CLOSED")));
+}
+
+#[tokio::test]
+async fn rugcheck_reports_are_served_from_cache_on_rescan() {
+    let p = pipeline("cache", |_| {}).await;
+
+    p.scan("pump").await;
+    p.scan("pump").await;
+
+    let report_calls = p.server.requests().iter().filter(|r| r.path.ends_with("/report")).count();
+    assert_eq!(report_calls, 3);
+    let stats = p.scanner.rug_client.cache().unwrap().stats();
+    assert_eq!((stats.hits, stats.misses, stats.entries), (3, 3, 3));
+}
+
+struct DenySymbol(&'static str);
+
+impl Rule for DenySymbol {
+    fn name(&self) -> &str {
+        "deny_symbol"
+    }
+
+    fn evaluate(&self, ctx: &RuleContext) -> Vec<Finding> {
+        if ctx.pair.base_token.symbol == self.0 {
+            return vec![Finding::reject(self.name(), MarketPattern::Blacklisted, -100.0, format!("{} is denied", self.0))];
+        }
+        vec![]
+    }
+}
+
+#[tokio::test]
+async fn custom_rules_are_evaluated_and_configurable() {
+    let mut p = pipeline("rules", |_| {}).await;
+    p.scanner.engine.rules_mut().register(DenySymbol("GOOD"));
+
+    let records = p.scan("pump").await;
+    assert_eq!(records[0].pattern, Some(MarketPattern::Blacklisted));
+    assert!(p.scanner.paper_engine.active_trades.lock().await.is_empty());
+
+    p.config.rules.insert("deny_symbol".to_string(), RuleSettings { enabled: false, weight: 1.0 });
+    let records = p.scan("pump").await;
+    assert_eq!(records[0].pattern, Some(MarketPattern::GoodCandidate));
+}
+
+#[tokio::test]
+async fn config_signals_classify_and_trade() {
+    let parsed = Config::from_toml_str(
+        r#"
+        [rules.m5_momentum]
+        enabled = false
+
+        [[signals]]
+        name = "h1_buyers"
+        when = "liquidity.usd > 20000 && txns.h1.buys / txns.h1.sells > 1.5 && price_change.h1 in 10..80"
+        trade = true
+        "#,
+    )
+    .unwrap();
+    let p = pipeline("signals", |c| {
+        c.rules = parsed.rules;
+        c.signals = parsed.signals;
+    })
+    .await;
+
+    let records = p.scan("pump").await;
+    assert_eq!(records[0].pattern, Some(MarketPattern::Custom("h1_buyers".to_string())));
+    assert_eq!(records[1].pattern, Some(MarketPattern::FakeVolume));
+    assert_eq!(p.scanner.paper_engine.active_trades.lock().await.len(), 1);
+
+    let err = Config::from_toml_str("[[signals]]\nname = \"x\"\nwhen = \"liquidity.usdd > 1\"\n").unwrap_err();
+    assert!(format!("{:#}", err).contains("unknown field `liquidity.usdd`"), "{:#}", err);
+    let err = Config::from_toml_str("[[signals]]\nname = \"x\"\nwhen = \"dex_id > 1\"\n").unwrap_err();
+    assert!(format!("{:#}", err).contains("cannot compare string with number"), "{:#}", err);
+}
+
+#[tokio::test]
+async fn blacklists_match_mint_pair_and_creator() {
+    let Pipeline { server: _server, mut config, dir, scanner } = pipeline("blacklist", |_| {}).await;
+    config.blacklist.file = Some(dir.join("blacklist.toml").to_string_lossy().into_owned());
+    let store = Arc::new(BlacklistStore::from_config(&config).unwrap());
+    let scanner = scanner.with_blacklist(Arc::clone(&store));
+
+    store.add(BlacklistKind::Dev, "GoodCreator1111111111111111111111111111111").unwrap();
+    let records = scanner.scan_query("pump", &config).await.unwrap();
+    let analysis = records[0].analysis.as_ref().unwrap();
+    assert_eq!(analysis.pattern, MarketPattern::Blacklisted);
+    assert!(analysis.reasons()[0].contains("dev blacklist"), "{:?}", analysis.reasons());
+    assert!(scanner.paper_engine.active_trades.lock().await.is_empty());
+
+    // Entries persist to the file and survive a reopen.
+    let reopened = BlacklistStore::from_config(&config).unwrap();
+    assert!(reopened.contains(BlacklistKind::Dev, "GoodCreator1111111111111111111111111111111"));
+
+    store.remove(BlacklistKind::Dev, "GoodCreator1111111111111111111111111111111").unwrap();
+    config.blacklist.tokens = vec![GOOD_MINT.to_string()];
+    let records = scanner.scan_query("pump", &config).await.unwrap();
+    let reasons = records[0].analysis.as_ref().unwrap().reasons();
+    assert_eq!(records[0].pattern, Some(MarketPattern::Blacklisted));
+    assert!(reasons[0].contains("token blacklist"), "{:?}", reasons);
+
+    config.blacklist.tokens.clear();
+    let records = scanner.scan_query("pump", &config).await.unwrap();
+    assert_eq!(records[0].pattern, Some(MarketPattern::GoodCandidate));
+}
+
+#[tokio::test]
+async fn rugcheck_risk_policies_name_the_failing_risks() {
+    let mut p = pipeline("risks", |c| c.rugcheck.only_good = false).await;
+
+    let records = p.scan("pump").await;
+    assert_eq!(records[2].pattern, Some(MarketPattern::GoodCandidate));
+
+    p.config.rugcheck.max_score = Some(5000);
+    p.config.rugcheck.deny_risks = vec!["mint authority".to_string()];
+    p.config.rugcheck.max_risks_per_level.insert("danger".to_string(), 1);
+    let records = p.scan("pump").await;
+    let analysis = records[2].analysis.as_ref().unwrap();
+    assert_eq!(analysis.pattern, MarketPattern::RugcheckRisk);
+    let reasons: Vec<String> = analysis.reasons().into_iter().filter(|r| r.starts_with("rugcheck_risks")).collect();
+    assert_eq!(reasons.len(), 3, "{:?}", reasons);
+    assert!(reasons[0].contains("risk score 18500 > max 5000"));
+    assert!(reasons[1].contains("Mint Authority still enabled"));
+    assert!(reasons[2].contains("2 \"danger\" risks > max 1: Mint Authority still enabled, Freeze Authority still enabled"));
+
+    // The good token carries no risks, so the same policies leave it alone.
+    assert_eq!(records[0].pattern, Some(MarketPattern::GoodCandidate));
+}
+
+#[tokio::test]
+async fn new_launch_mode_scans_youngest_first_and_tags_the_alert() {
+    let p = pipeline("launch", |c| {
+        c.new_launch.enabled = true;
+        c.rugcheck.only_good = false;
+        c.filters.max_pair_age_minutes = Some(24.0 * 60.0);
+    })
+    .await;
+    let ten_minutes_ago = (chrono::Utc::now().timestamp() - 600) * 1000;
+    let mut fresh = fixture_pair(0);
+    fresh["pairAddress"] = "FreshPair".into();
+    fresh["baseToken"]["symbol"] = "FRESH".into();
+    fresh["pairCreatedAt"] = ten_minutes_ago.into();
+    fresh["priceChange"]["m5"] = 2.0.into();
+    let mut risky = fixture_pair(2);
+    risky["pairCreatedAt"] = ten_minutes_ago.into();
+    p.serve("launch", vec![fixture_pair(0), fresh, risky]);
+
+    let records = p.scan("launch").await;
+
+    let symbols: Vec<&str> = records.iter().map(|r| r.pair.base_token.symbol.as_str()).collect();
+    assert_eq!(symbols, ["FRESH", "RISK", "GOOD"]);
+    let alert = &p.alerts()[0];
+    assert!(alert.contains("NEW LAUNCH") && alert.contains("Age: 10m"), "{}", alert);
+}
+
+#[tokio::test]
+async fn liquidity_pull_on_a_held_token_forces_an_exit() {
+    let watcher = Arc::new(LiquidityWatcher::new());
+    let engine = Arc::new(PaperTradingEngine::new().with_watcher(Arc::clone(&watcher)));
+    let p = pipeline("pull", |_| {}).await.with_engine(Arc::clone(&engine)).await;
+    let monitor = || engine.monitor_trades(&p.scanner.dex_client, &p.config, &p.scanner.notifier);
+
+    p.scan("pump").await;
+    assert!(watcher.is_watching(GOOD_MINT));
+    monitor().await;
+    assert_eq!(engine.active_trades.lock().await.len(), 1);
+
+    let key = format!("latest/dex/tokens/{}", GOOD_MINT);
+    let mut pulled: serde_json::Value = serde_json::from_str(&fixture(&key)).unwrap();
+    pulled["pairs"][0]["liquidity"] = serde_json::json!({"usd": 4000.0, "base": 2000000.0, "quote": 13.3});
+    p.server.set_response(&key, pulled.to_string());
+
+    monitor().await;
+
+    assert!(engine.active_trades.lock().await.is_empty());
+    assert_eq!(engine.closed_trades.lock().await[0].reason, ExitReason::LiquidityPull);
+    let alert = p.alerts().into_iter().find(|a| a.contains("LIQUIDITY PULLED")).unwrap();
+    assert!(alert.contains("Forced paper exit"), "{}", alert);
+}
+
+#[tokio::test]
+async fn a_pull_that_leaves_no_price_books_a_total_loss() {
+    let watcher = Arc::new(LiquidityWatcher::new());
+    let engine = Arc::new(PaperTradingEngine::new().with_watcher(Arc::clone(&watcher)));
+    let p = pipeline("pull-no-price", |_| {}).await.with_engine(Arc::clone(&engine)).await;
+    let monitor = || engine.monitor_trades(&p.scanner.dex_client, &p.config, &p.scanner.notifier);
+
+    p.scan("pump").await;
+    monitor().await;
+
+    let key = format!("latest/dex/tokens/{}", GOOD_MINT);
+    let mut drained: serde_json::Value = serde_json::from_str(&fixture(&key)).unwrap();
+    drained["pairs"][0]["liquidity"] = serde_json::json!({"usd": 0.0, "base": 0.0, "quote": 0.0});
+    drained["pairs"][0]["priceUsd"] = serde_json::Value::Null;
+    p.server.set_response(&key, drained.to_string());
+
+    monitor().await;
+
+    assert!(engine.active_trades.lock().await.is_empty());
+    let closed = engine.closed_trades.lock().await[0].clone();
+    assert_eq!((closed.reason, closed.exit_price), (ExitReason::LiquidityPull, 0.0));
+    // All of the stake, plus the entry fees.
+    assert!(closed.pnl_percent <= -100.0, "{}", closed.pnl_percent);
+    let alert = p.alerts().into_iter().find(|a| a.contains("LIQUIDITY PULLED")).unwrap();
+    assert!(alert.contains("Forced paper exit"), "{}", alert);
+}
+
+#[tokio::test]
+async fn a_delisted_pair_or_missing_liquidity_reads_as_a_pull() {
+    let key = format!("latest/dex/tokens/{}", GOOD_MINT);
+    let no_liquidity = {
+        let mut value: serde_json::Value = serde_json::from_str(&fixture(&key)).unwrap();
+        value["pairs"][0].as_object_mut().unwrap().remove("liquidity");
+        value.to_string()
+    };
+    for (name, response) in [("pull-delisted", r#"{"pairs": []}"#.to_string()), ("pull-no-liquidity", no_liquidity)] {
+        let watcher = Arc::new(LiquidityWatcher::new());
+        let engine = Arc::new(PaperTradingEngine::new().with_watcher(Arc::clone(&watcher)));
+        let p = pipeline(name, |_| {}).await.with_engine(Arc::clone(&engine)).await;
+        let monitor = || engine.monitor_trades(&p.scanner.dex_client, &p.config, &p.scanner.notifier);
+
+        p.scan("pump").await;
+        monitor().await;
+        p.server.set_response(&key, response);
+        monitor().await;
+
+        assert!(engine.active_trades.lock().await.is_empty(), "{}", name);
+        assert_eq!(engine.closed_trades.lock().await[0].reason, ExitReason::LiquidityPull, "{}", name);
+        let alert = p.alerts().into_iter().find(|a| a.contains("LIQUIDITY PULLED")).unwrap();
+        assert!(alert.contains("GOOD") && alert.contains("Forced paper exit"), "{}", alert);
+    }
+}
+
+#[tokio::test]
+async fn one_alert_per_token_led_by_its_best_pair_then_cooled_down() {
+    let mut p = pipeline("dedup", |c| c.paper_trading.enabled = false).await;
+    let mut second = fixture_pair(0);
+    second["pairAddress"] = "SecondPair".into();
+    second["dexId"] = "orca".into();
+    second["liquidity"]["usd"] = 20000.0.into();
+    p.serve("dups", vec![second, fixture_pair(0)]);
+
+    // Two qualifying pairs of one mint make one alert, led by the best-scoring, deepest pair.
+    let records = p.scan("dups").await;
+    assert!(records.iter().all(|r| r.pattern == Some(MarketPattern::GoodCandidate)));
+    let alerts = p.alerts();
+    assert_eq!(alerts.len(), 1);
+    assert!(alerts[0].contains("SecondPair") && alerts[0].contains("orca"), "{}", alerts[0]);
+
+    // The same token from another query is within its cooldown.
+    p.scan("pump").await;
+    assert_eq!(p.alerts().len(), 1);
+
+    // Until the score clears the re-alert delta.
+    p.config.alerts.realert_score_delta = 0.5;
+    p.config.rules.insert("m5_momentum".to_string(), RuleSettings { enabled: true, weight: 2.0 });
+    p.scan("pump").await;
+    let alerts = p.alerts();
+    assert_eq!(alerts.len(), 2);
+    assert!(alerts[1].contains("Score up from"), "{}", alerts[1]);
+}
+
+#[tokio::test]
+async fn a_token_is_entered_on_the_pair_it_is_alerted_on() {
+    let p = pipeline("best-pair", |c| c.execution.enabled = false).await;
+    let mut shallow = fixture_pair(0);
+    shallow["pairAddress"] = "ShallowPair".into();
+    shallow["liquidity"]["usd"] = 20000.0.into();
+    shallow["priceUsd"] = "0.0011".into();
+    // Same score, so the deeper pool leads, though the shallow one is scanned first.
+    p.serve("dups", vec![shallow, fixture_pair(0)]);
+
+    p.scan("dups").await;
+
+    let alert = p.alerts().into_iter().find(|a| a.contains("GOOD SIGNAL")).unwrap();
+    let (lead, others) = alert.split_once("Also on").unwrap();
+    assert!(lead.contains("Liq: $Some") && lead.contains("50000") && others.contains("ShallowPair"), "{}", alert);
+    let trades = p.scanner.paper_engine.active_trades.lock().await;
+    assert_eq!(trades.len(), 1);
+    assert_eq!(trades[0].entry_price, 0.001);
+}
+
+#[tokio::test]
+async fn merged_queries_security_check_and_record_each_pair_once() {
+    let p = pipeline("merge", |_| {}).await;
+    let mut fresher = fixture_pair(0);
+    fresher["volume"]["h24"] = 123456.0.into();
+    p.serve("solana", vec![fresher]);
+
+    let mut batch = ScanBatch::new();
+    assert_eq!(p.scanner.gather(&mut batch, "pump").await.unwrap(), 3);
+    assert_eq!(p.scanner.gather(&mut batch, "solana").await.unwrap(), 1);
+    let records = p.scanner.scan_batch(batch, &p.config).await;
+
+    assert_eq!(records.len(), 3);
+    assert_eq!(records[0].queries, ["pump", "solana"]);
+    assert_eq!(records[0].pair.volume.h24, 123456.0);
+    let rugchecks = p.server.requests().into_iter().filter(|r| r.path.contains(GOOD_MINT) && r.path.ends_with("/report")).count();
+    assert_eq!(rugchecks, 1);
+    assert_eq!(p.scanner.db.load_snapshots().await.unwrap().records.len(), 3);
+}
+
+#[tokio::test]
+async fn disallowed_chains_are_dropped_and_security_checks_follow_the_chain() {
+    let p = pipeline("chains", |c| {
+        c.chains.allow = vec!["solana".to_string(), "base".to_string()];
+        c.chains.profiles.insert("base".to_string(), ChainProfile::default());
+    })
+    .await;
+    let mut base = fixture_pair(0);
+    base["chainId"] = "base".into();
+    base["pairAddress"] = "0xbasepair".into();
+    base["baseToken"]["address"] = "0xbasemint".into();
+    let mut bsc = base.clone();
+    bsc["chainId"] = "bsc".into();
+    bsc["pairAddress"] = "0xbscpair".into();
+    p.serve("chains", vec![fixture_pair(0), base, bsc]);
+
+    let records = p.scan("chains").await;
+
+    let addresses: Vec<&str> = records.iter().map(|r| r.pair.pair_address.as_str()).collect();
+    assert_eq!(addresses, ["GoodPair11111111111111111111111111111111111", "0xbasepair"]);
+    assert!(records[0].rugcheck.is_some() && records[1].rugcheck.is_none());
+    assert!(!p.server.requests().iter().any(|r| r.path.contains("0xbasemint") || r.path.contains("0xbsc")));
+}
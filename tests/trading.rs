@@ -0,0 +1,323 @@
+mod common;
+
+use common::{fixture_pair, good_pair, good_report, pair, scratch_dir, FIXTURES, GOOD_MINT, NOW};
+use dex_bot::backtest::Backtester;
+use dex_bot::client::DexScreenerClient;
+use dex_bot::config::{Config, TakeProfitStep};
+use dex_bot::execution::{self, WSOL_MINT};
+use dex_bot::mock::MockServer;
+use dex_bot::models::Pair;
+use dex_bot::portfolio::SolPriceFeed;
+use dex_bot::storage::{EquityCurve, TradeJournal};
+use dex_bot::trading::{ClosedTrade, ExitReason, PaperTradingEngine};
+use std::io::Write;
+use std::sync::Arc;
+
+const WASH_MINT: &str = "WashMint11111111111111111111111111111111111";
+const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+const USDT_MINT: &str = "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB";
+
+/// The good fixture pair quoted at `price` USD.
+fn priced(price: f64) -> Pair {
+    let mut value = fixture_pair(0);
+    value["priceUsd"] = price.to_string().into();
+    pair(value)
+}
+
+/// Replays the good fixture pair at each `(seconds, price)` and returns the first closed trade.
+async fn replay_prices(config: &Config, prices: &[(i64, f64)]) -> ClosedTrade {
+    let mut backtester = Backtester::new();
+    for (secs, price) in prices {
+        backtester.step(&priced(*price), Some(&good_report()), NOW + secs, config).await;
+    }
+    backtester.finish(config).await.remove(0)
+}
+
+fn ladder_config() -> Config {
+    let mut config = Config::default();
+    config.execution.enabled = false;
+    config.paper_trading.take_profit_ladder = vec![
+        TakeProfitStep { at_percent: 50.0, sell_percent: 50.0 },
+        TakeProfitStep { at_percent: 100.0, sell_percent: 25.0 },
+    ];
+    config.paper_trading.trailing_stop_percent = Some(30.0);
+    config.validate().unwrap();
+    config
+}
+
+#[tokio::test]
+async fn fills_pay_price_impact_swap_and_priority_fees() {
+    let config = Config::default();
+    let engine = PaperTradingEngine::new();
+    engine.process_signal_at(&good_pair(), &config, NOW).await;
+
+    let trade = engine.active_trades.lock().await[0].clone();
+    assert!(trade.entry_price > 0.001 * 1.0025, "{}", trade.entry_price);
+    assert!((trade.tokens * trade.entry_price - 0.1 * 150.0).abs() < 1e-6);
+    assert_eq!(trade.fees_sol, config.execution.priority_fee_sol);
+
+    let closed = engine.manage_position(GOOD_MINT, &priced(0.002), NOW + 60, &config).await.unwrap();
+    assert_eq!(closed.reason, ExitReason::TakeProfit);
+    assert!(closed.exit_price < 0.002);
+    assert!(closed.pnl_percent > 95.0 && closed.pnl_percent < 100.0, "{}", closed.pnl_percent);
+    assert_eq!(closed.fees_sol, 2.0 * config.execution.priority_fee_sol);
+}
+
+#[tokio::test]
+async fn entries_into_pools_too_thin_for_the_size_are_rejected() {
+    let config = Config::default();
+    // 0.1 SOL into a pool holding 0.5 SOL moves the price ~20%, past the 10% tolerance.
+    let mut thin = fixture_pair(0);
+    thin["liquidity"] = serde_json::json!({"usd": 150.0, "base": 75000.0, "quote": 0.5});
+    let thin = pair(thin);
+    let fill = execution::buy(&thin, 0.1, 150.0, &config.execution).unwrap();
+    assert!(fill.impact_percent.unwrap() > 19.0 && fill.exceeds_tolerance(&config.execution), "{:?}", fill);
+
+    let engine = PaperTradingEngine::new();
+    engine.process_signal_at(&thin, &config, NOW).await;
+    assert!(engine.active_trades.lock().await.is_empty());
+}
+
+#[tokio::test]
+async fn ladder_sells_in_steps_and_trails_out_the_rest() {
+    // Half sold at +50%, a quarter at +110%, the rest trailed out at +40%.
+    let trailed = replay_prices(&ladder_config(), &[(0, 0.001), (60, 0.0015), (120, 0.0021), (180, 0.0014)]).await;
+    assert_eq!(trailed.reason, ExitReason::TrailingStop);
+    let sold: Vec<(f64, f64)> = trailed.partial_fills.iter().map(|p| (p.fraction, p.price)).collect();
+    assert_eq!(sold, [(0.5, 0.0015), (0.25, 0.0021)]);
+    assert!((trailed.pnl_percent - 62.5).abs() < 1e-6, "{}", trailed.pnl_percent);
+}
+
+#[tokio::test]
+async fn breakeven_stop_follows_the_first_take_profit() {
+    let mut config = ladder_config();
+    config.paper_trading.breakeven_after_first_tp = true;
+    let breakeven = replay_prices(&config, &[(0, 0.001), (60, 0.0015), (120, 0.001)]).await;
+    assert_eq!(breakeven.reason, ExitReason::Breakeven);
+    assert!((breakeven.pnl_percent - 25.0).abs() < 1e-6, "{}", breakeven.pnl_percent);
+}
+
+#[tokio::test]
+async fn positions_time_out_after_max_hold() {
+    let mut config = ladder_config();
+    config.paper_trading.max_hold_secs = Some(600);
+    let timed_out = replay_prices(&config, &[(0, 0.001), (300, 0.0011), (700, 0.0011)]).await;
+    assert_eq!(timed_out.reason, ExitReason::MaxHoldTime);
+    assert_eq!(timed_out.hold_secs, 700);
+}
+
+#[tokio::test]
+async fn protective_exits_fill_past_the_slippage_tolerance_and_take_profits_wait() {
+    let config = Config::default();
+    let engine = PaperTradingEngine::new();
+    engine.process_signal_at(&good_pair(), &config, NOW).await;
+    // A pool so thin that selling the position moves the price far past the tolerance.
+    let thin = |price: f64| {
+        let mut value = fixture_pair(0);
+        value["priceUsd"] = price.to_string().into();
+        value["liquidity"] = serde_json::json!({"usd": 100.0, "base": 1000.0, "quote": 0.5});
+        pair(value)
+    };
+
+    assert!(engine.manage_position(GOOD_MINT, &thin(0.002), NOW + 60, &config).await.is_none());
+    assert_eq!(engine.active_trades.lock().await.len(), 1);
+
+    let closed = engine.manage_position(GOOD_MINT, &thin(0.0005), NOW + 120, &config).await.unwrap();
+    assert_eq!(closed.reason, ExitReason::StopLoss);
+    assert!(closed.exit_price < 0.0005 * 0.1, "{}", closed.exit_price);
+
+    let mut config = ladder_config();
+    config.execution = Config::default().execution;
+    config.paper_trading.max_hold_secs = Some(600);
+    let engine = PaperTradingEngine::new();
+    engine.process_signal_at(&good_pair(), &config, NOW).await;
+    let closed = engine.manage_position(GOOD_MINT, &thin(0.001), NOW + 700, &config).await.unwrap();
+    assert_eq!(closed.reason, ExitReason::MaxHoldTime);
+}
+
+#[test]
+fn ladders_must_climb_and_sell_at_most_everything() {
+    let mut config = ladder_config();
+    config.paper_trading.take_profit_ladder.push(TakeProfitStep { at_percent: 90.0, sell_percent: 50.0 });
+    let err = config.validate().unwrap_err().to_string();
+    assert!(err.contains("take_profit_ladder[2].at_percent must be above") && err.contains("125% in total"), "{}", err);
+}
+
+/// A wrapped-SOL pool quoted in `quote` at `price` USD.
+fn sol_pool(quote: &str, quote_mint: &str, price: &str, liquidity: f64) -> serde_json::Value {
+    let mut pool = fixture_pair(0);
+    pool["pairAddress"] = format!("SOL{}{}", quote, liquidity).into();
+    pool["baseToken"] = serde_json::json!({"address": WSOL_MINT, "name": "Wrapped SOL", "symbol": "SOL"});
+    pool["quoteToken"] = serde_json::json!({"address": quote_mint, "name": quote, "symbol": quote});
+    pool["priceNative"] = price.into();
+    pool["priceUsd"] = price.into();
+    pool["liquidity"] = serde_json::json!({"usd": liquidity, "base": 0.0, "quote": 0.0});
+    pool
+}
+
+#[tokio::test]
+async fn sol_price_comes_from_the_deepest_stable_pool_and_is_cached() {
+    let config = Config::default();
+    let pools = vec![
+        sol_pool("USDT", USDT_MINT, "190", 1_000.0),
+        sol_pool("USDC", USDC_MINT, "200", 1_000_000.0),
+        sol_pool("MEME", "MemeMint", "500", 1e9),
+        // Deepest of all, but only named like a stablecoin.
+        sol_pool("USDC", "FakeUSDC", "900", 1e10),
+    ];
+    assert_eq!(SolPriceFeed::price_from_pairs(&pools.iter().cloned().map(pair).collect::<Vec<_>>(), &config), Some(200.0));
+
+    let server = MockServer::start(FIXTURES, "127.0.0.1:0").await.unwrap();
+    server.set_response(&format!("latest/dex/tokens/{}", WSOL_MINT), serde_json::json!({ "pairs": pools }).to_string());
+    let client = DexScreenerClient::with_base_url(&server.url()).unwrap();
+    let feed = SolPriceFeed::new();
+    assert_eq!(feed.price_or_fallback(&config), config.execution.sol_price_usd);
+    assert_eq!(feed.refresh(&client, &config, NOW).await, Some(200.0));
+    assert_eq!(feed.refresh(&client, &config, NOW + 30).await, Some(200.0));
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn entries_the_balance_cannot_cover_are_skipped() {
+    let mut config = Config::default();
+    config.execution.enabled = false;
+    config.portfolio.starting_balance_sol = 0.15;
+
+    // 0.15 SOL covers one 0.1 SOL entry but not a second.
+    let engine = PaperTradingEngine::new();
+    engine.process_signal_at(&good_pair(), &config, NOW).await;
+    engine.process_signal_at(&pair(fixture_pair(1)), &config, NOW).await;
+    assert_eq!(engine.active_trades.lock().await.len(), 1);
+}
+
+/// Serves a wrapped-SOL/USDC pool at `price` and refreshes `feed` from it as of `now`.
+async fn refresh_sol(server: &MockServer, feed: &SolPriceFeed, config: &Config, price: &str, now: i64) -> Option<f64> {
+    let pools = serde_json::json!({ "pairs": [sol_pool("USDC", USDC_MINT, price, 1e6)] });
+    server.set_response(&format!("latest/dex/tokens/{}", WSOL_MINT), pools.to_string());
+    feed.refresh(&DexScreenerClient::with_base_url(&server.url()).unwrap(), config, now).await
+}
+
+#[tokio::test]
+async fn portfolio_marks_positions_and_books_closed_pnl_in_sol_and_usd() {
+    let mut config = Config::default();
+    config.execution.enabled = false;
+    config.portfolio.starting_balance_sol = 0.15;
+    let server = MockServer::start(FIXTURES, "127.0.0.1:0").await.unwrap();
+    let feed = Arc::new(SolPriceFeed::new());
+    // Quoted in USDC, so fills take the feed's SOL price.
+    let usdc_quoted = |price: f64| {
+        let mut value = fixture_pair(0);
+        value["quoteToken"] = serde_json::json!({"address": USDC_MINT, "name": "USD Coin", "symbol": "USDC"});
+        value["priceUsd"] = price.to_string().into();
+        value["priceNative"] = price.to_string().into();
+        pair(value)
+    };
+    let engine = PaperTradingEngine::new().with_sol_price(Arc::clone(&feed));
+
+    // 0.1 SOL buys $15 of tokens at SOL $150.
+    refresh_sol(&server, &feed, &config, "150", NOW).await;
+    engine.process_signal_at(&usdc_quoted(0.001), &config, NOW).await;
+    assert_eq!(engine.active_trades.lock().await[0].entry_sol_usd, Some(150.0));
+
+    // Up 20% in USD, but SOL rallied to $200: the $18 position is only worth 0.09 SOL.
+    let later = NOW + config.portfolio.sol_price_ttl_secs as i64;
+    refresh_sol(&server, &feed, &config, "200", later).await;
+    assert!(engine.manage_position(GOOD_MINT, &usdc_quoted(0.0012), later, &config).await.is_none());
+    let open = engine.portfolio(&config, later).await;
+    assert!((open.cash_sol - 0.05).abs() < 1e-9, "{:?}", open);
+    assert!((open.positions_sol - 0.09).abs() < 1e-9, "{:?}", open);
+    assert!((open.unrealized_pnl_sol + 0.01).abs() < 1e-9, "{:?}", open);
+    assert!((open.unrealized_pnl_usd - 3.0).abs() < 1e-6, "{:?}", open);
+    assert!((open.equity_usd - 28.0).abs() < 1e-6, "{:?}", open);
+    assert_eq!(open.open_positions, 1);
+
+    // Sold for $22.50 = 0.1125 SOL; the USD gain is against the $15 paid, not today's SOL price.
+    assert!(engine.manage_position(GOOD_MINT, &usdc_quoted(0.0015), later + 60, &config).await.is_some());
+    let closed = engine.portfolio(&config, later + 60).await;
+    assert!((closed.realized_pnl_sol - 0.0125).abs() < 1e-9, "{:?}", closed);
+    assert!((closed.realized_pnl_usd - 7.5).abs() < 1e-6, "{:?}", closed);
+    assert!((closed.equity_sol - 0.1625).abs() < 1e-9, "{:?}", closed);
+    assert_eq!(closed.open_positions, 0);
+    let trade = engine.closed_trades.lock().await[0].clone();
+    assert_eq!((trade.entry_sol_usd, trade.exit_sol_usd), (Some(150.0), Some(200.0)));
+
+    // SOL moving after the close doesn't rewrite what was realized.
+    refresh_sol(&server, &feed, &config, "300", later * 2 - NOW).await;
+    let after = engine.portfolio(&config, later + 120).await;
+    assert!((after.realized_pnl_usd - 7.5).abs() < 1e-6, "{:?}", after);
+
+    let curve = EquityCurve::new(scratch_dir("equity").join("equity.jsonl").to_str().unwrap()).unwrap();
+    curve.append(&open).unwrap();
+    curve.append(&closed).unwrap();
+    let loaded = curve.load().unwrap();
+    assert_eq!(loaded.len(), 2);
+    for (loaded, saved) in loaded.iter().zip([&open, &closed]) {
+        // JSON floats may come back a bit off in the last digit.
+        assert_eq!((loaded.ts, loaded.open_positions), (saved.ts, saved.open_positions));
+        assert!((loaded.equity_sol - saved.equity_sol).abs() < 1e-12 && (loaded.realized_pnl_usd - saved.realized_pnl_usd).abs() < 1e-9);
+    }
+}
+
+#[tokio::test]
+async fn positions_survive_a_restart_through_the_journal() {
+    let config = ladder_config();
+    let path = scratch_dir("journal").join("trades.jsonl");
+    let wash = |price: f64| {
+        let mut value = fixture_pair(1);
+        value["priceUsd"] = price.to_string().into();
+        pair(value)
+    };
+    let engine = PaperTradingEngine::with_journal(TradeJournal::new(path.to_str().unwrap()).unwrap()).unwrap();
+    engine.process_signal_at(&good_pair(), &config, NOW).await;
+    engine.process_signal_at(&wash(0.0004), &config, NOW).await;
+    assert!(engine.manage_position(GOOD_MINT, &priced(0.0015), NOW + 60, &config).await.is_none());
+    let stopped = engine.manage_position(WASH_MINT, &wash(0.0002), NOW + 60, &config).await.unwrap();
+    assert_eq!(stopped.reason, ExitReason::StopLoss);
+    drop(engine);
+
+    let mut file = std::fs::OpenOptions::new().append(true).open(&path).unwrap();
+    writeln!(file, "{{\"event\":\"open\",\"address\":").unwrap();
+    writeln!(file).unwrap();
+
+    // The open position comes back with its partial fill, the closed one as history.
+    let restarted = PaperTradingEngine::with_journal(TradeJournal::new(path.to_str().unwrap()).unwrap()).unwrap();
+    let open = restarted.active_trades.lock().await.clone();
+    assert_eq!(open.len(), 1);
+    assert_eq!(open[0].address, GOOD_MINT);
+    assert_eq!(open[0].partial_fills.len(), 1);
+    assert!((open[0].remaining() - 0.5).abs() < 1e-9);
+    let closed = restarted.closed_trades.lock().await.clone();
+    assert_eq!(closed.len(), 1);
+    assert_eq!((closed[0].address.as_str(), closed[0].reason.clone()), (WASH_MINT, ExitReason::StopLoss));
+
+    // The reused journal keeps growing: closing the restored position is recorded after the corrupt line.
+    let closed = restarted.manage_position(GOOD_MINT, &priced(0.0021), NOW + 120, &config).await;
+    assert!(closed.is_none(), "the second ladder step sells a quarter");
+    restarted.manage_position(GOOD_MINT, &priced(0.0012), NOW + 180, &config).await.unwrap();
+    let (open, closed) = TradeJournal::new(path.to_str().unwrap()).unwrap().load().unwrap();
+    assert!(open.is_empty());
+    assert_eq!(closed.len(), 2);
+    assert_eq!(closed[1].partial_fills.len(), 2);
+}
+
+#[tokio::test]
+async fn trailing_stops_keep_their_peak_across_a_restart() {
+    let config = ladder_config();
+    let path = scratch_dir("journal-peak").join("trades.jsonl");
+    let journal = || TradeJournal::new(path.to_str().unwrap()).unwrap();
+    let engine = PaperTradingEngine::with_journal(journal()).unwrap();
+    engine.process_signal_at(&good_pair(), &config, NOW).await;
+    // Up 40%: a new high, below the first take-profit step.
+    assert!(engine.manage_position(GOOD_MINT, &priced(0.0014), NOW + 60, &config).await.is_none());
+    // Lower highs don't add to the journal.
+    assert!(engine.manage_position(GOOD_MINT, &priced(0.0012), NOW + 120, &config).await.is_none());
+    drop(engine);
+    let marks = std::fs::read_to_string(&path).unwrap().lines().filter(|l| l.contains("\"event\":\"mark\"")).count();
+    assert_eq!(marks, 1);
+
+    let restarted = PaperTradingEngine::with_journal(journal()).unwrap();
+    assert_eq!(restarted.active_trades.lock().await[0].peak_price, 0.0014);
+    // 30% off the restored peak, though still near the entry price.
+    let closed = restarted.manage_position(GOOD_MINT, &priced(0.00097), NOW + 180, &config).await.unwrap();
+    assert_eq!(closed.reason, ExitReason::TrailingStop);
+}